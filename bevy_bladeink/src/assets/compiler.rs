@@ -0,0 +1,369 @@
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+    process::Command,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use bevy::platform::collections::HashMap;
+use thiserror::Error;
+
+/// The source of an ink story, along with the files it includes.
+#[derive(Debug, Clone, Default)]
+pub struct InkSource {
+    /// Asset path of the root `.ink` file.
+    pub path: String,
+    /// Contents of the root file.
+    pub text: String,
+    /// Contents of every file included by the story, keyed by the path it
+    /// was included with, relative to the root file.
+    pub includes: HashMap<String, String>,
+}
+
+/// How severe a message reported by the compiler is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InkDiagnosticSeverity {
+    /// The story failed to compile.
+    Error,
+    /// The story compiled, but likely doesn't behave as intended.
+    Warning,
+    /// A `TODO:` note left in the story.
+    Todo,
+}
+
+/// A message reported by the compiler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InkDiagnostic {
+    /// How severe the message is.
+    pub severity: InkDiagnosticSeverity,
+    /// The file the message refers to, if any.
+    pub file: Option<String>,
+    /// The line the message refers to, if any.
+    pub line: Option<u32>,
+    /// The text of the message.
+    pub message: String,
+}
+
+impl InkDiagnostic {
+    /// Creates an error which doesn't refer to any line.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: InkDiagnosticSeverity::Error,
+            file: None,
+            line: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for InkDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            InkDiagnosticSeverity::Error => "ERROR",
+            InkDiagnosticSeverity::Warning => "WARNING",
+            InkDiagnosticSeverity::Todo => "TODO",
+        };
+        write!(f, "{severity}: ")?;
+        if let Some(file) = &self.file {
+            write!(f, "'{file}' ")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Story compiled from ink source.
+#[derive(Debug, Clone, Default)]
+pub struct InkCompiled {
+    /// The compiled story, as loaded from `.ink.json` files.
+    pub json: String,
+    /// Warnings and todos reported while compiling.
+    pub diagnostics: Vec<InkDiagnostic>,
+}
+
+/// Possible errors produced while compiling ink source.
+#[derive(Debug, Error)]
+pub enum InkCompileError {
+    /// The compiler could not be run.
+    #[error("Could not run the ink compiler: {0}")]
+    Backend(String),
+    /// The source does not compile.
+    #[error("{}", format_diagnostics(.0))]
+    Diagnostics(Vec<InkDiagnostic>),
+}
+
+fn format_diagnostics(diagnostics: &[InkDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compiles ink source into the JSON format read by the runtime. Installed
+/// with [`use_ink_compiler`](crate::assets::AddInkCompilerApp::use_ink_compiler).
+pub trait InkCompiler: Send + Sync + 'static {
+    /// Compiles the story. Runs on an IO task, so it may block.
+    fn compile(&self, source: &InkSource) -> Result<InkCompiled, InkCompileError>;
+}
+
+/// Compiles stories with a locally installed `inklecate` executable.
+#[derive(Debug, Clone)]
+pub struct InklecateCompiler {
+    executable: PathBuf,
+}
+
+impl Default for InklecateCompiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InklecateCompiler {
+    /// Runs `inklecate` from the `PATH`.
+    pub fn new() -> Self {
+        Self {
+            executable: PathBuf::from("inklecate"),
+        }
+    }
+
+    /// Runs the executable at the given path instead.
+    pub fn with_executable(mut self, executable: impl Into<PathBuf>) -> Self {
+        self.executable = executable.into();
+        self
+    }
+
+    /// Writes the source to a fresh directory, so includes resolve the same
+    /// way they do next to the root file.
+    fn write_source(dir: &Path, source: &InkSource) -> Result<PathBuf, InkCompileError> {
+        let backend = |err: std::io::Error| InkCompileError::Backend(err.to_string());
+        let root = dir.join(file_name(&source.path));
+        std::fs::create_dir_all(dir).map_err(backend)?;
+        std::fs::write(&root, &source.text).map_err(backend)?;
+        for (include, text) in &source.includes {
+            let relative = Path::new(include);
+            if relative
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)))
+            {
+                return Err(InkCompileError::Diagnostics(vec![InkDiagnostic::error(
+                    format!("Included file '{include}' is outside of the story's directory"),
+                )]));
+            }
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(backend)?;
+            }
+            std::fs::write(path, text).map_err(backend)?;
+        }
+        Ok(root)
+    }
+}
+
+impl InkCompiler for InklecateCompiler {
+    fn compile(&self, source: &InkSource) -> Result<InkCompiled, InkCompileError> {
+        static NEXT_DIR: AtomicU64 = AtomicU64::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "bevy_bladeink-{}-{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = Self::write_source(&dir, source).and_then(|root| {
+            let output = root.with_extension("ink.json");
+            let run = Command::new(&self.executable)
+                .arg("-o")
+                .arg(&output)
+                .arg(&root)
+                .output()
+                .map_err(|err| {
+                    InkCompileError::Backend(format!("{}: {err}", self.executable.display()))
+                })?;
+            let stdout = String::from_utf8_lossy(&run.stdout);
+            let stderr = String::from_utf8_lossy(&run.stderr);
+            let diagnostics = parse_diagnostics(&format!("{stdout}\n{stderr}"));
+            if !run.status.success()
+                || diagnostics
+                    .iter()
+                    .any(|diagnostic| diagnostic.severity == InkDiagnosticSeverity::Error)
+            {
+                if diagnostics.is_empty() {
+                    return Err(InkCompileError::Backend(format!(
+                        "inklecate exited with {}: {}",
+                        run.status,
+                        stderr.trim()
+                    )));
+                }
+                return Err(InkCompileError::Diagnostics(diagnostics));
+            }
+            let json = std::fs::read_to_string(&output)
+                .map_err(|err| InkCompileError::Backend(err.to_string()))?;
+            Ok(InkCompiled { json, diagnostics })
+        });
+        // the directory is only scratch space
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Parses the messages `inklecate` prints, e.g.
+/// `ERROR: 'main.ink' line 3: Unexpected token`.
+pub(crate) fn parse_diagnostics(output: &str) -> Vec<InkDiagnostic> {
+    output.lines().filter_map(parse_diagnostic).collect()
+}
+
+fn parse_diagnostic(line: &str) -> Option<InkDiagnostic> {
+    let line = line.trim();
+    let (severity, rest) = [
+        ("ERROR:", InkDiagnosticSeverity::Error),
+        ("WARNING:", InkDiagnosticSeverity::Warning),
+        ("TODO:", InkDiagnosticSeverity::Todo),
+    ]
+    .into_iter()
+    .find_map(|(prefix, severity)| Some((severity, line.strip_prefix(prefix)?.trim_start())))?;
+
+    let (file, rest) = match rest
+        .strip_prefix('\'')
+        .and_then(|rest| rest.split_once('\''))
+    {
+        Some((file, rest)) => (Some(file.to_string()), rest.trim_start()),
+        None => (None, rest),
+    };
+    let (line, message) = match rest
+        .strip_prefix("line ")
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(line, message)| Some((line.trim().parse().ok()?, message)))
+    {
+        Some((line, message)) => (Some(line), message.trim()),
+        None => (None, rest),
+    };
+    Some(InkDiagnostic {
+        severity,
+        file,
+        line,
+        message: message.to_string(),
+    })
+}
+
+/// Compiler returning preset output instead of compiling, for tests and for
+/// platforms without `inklecate`. Records every source it is asked to compile.
+#[derive(Debug, Clone, Default)]
+pub struct StubInkCompiler {
+    outputs: HashMap<String, Result<InkCompiled, Vec<InkDiagnostic>>>,
+    sources: Arc<Mutex<Vec<InkSource>>>,
+}
+
+impl StubInkCompiler {
+    /// Creates a compiler without any preset output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compiles the story at the given asset path to the given JSON.
+    pub fn with_story(mut self, path: impl Into<String>, json: impl Into<String>) -> Self {
+        self.outputs.insert(
+            path.into(),
+            Ok(InkCompiled {
+                json: json.into(),
+                diagnostics: Vec::new(),
+            }),
+        );
+        self
+    }
+
+    /// Fails to compile the story at the given asset path.
+    pub fn with_errors(mut self, path: impl Into<String>, errors: Vec<InkDiagnostic>) -> Self {
+        self.outputs.insert(path.into(), Err(errors));
+        self
+    }
+
+    /// The sources compiled so far.
+    pub fn sources(&self) -> Vec<InkSource> {
+        self.sources
+            .lock()
+            .map(|sources| sources.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl InkCompiler for StubInkCompiler {
+    fn compile(&self, source: &InkSource) -> Result<InkCompiled, InkCompileError> {
+        if let Ok(mut sources) = self.sources.lock() {
+            sources.push(source.clone());
+        }
+        match self.outputs.get(&source.path) {
+            Some(Ok(compiled)) => Ok(compiled.clone()),
+            Some(Err(errors)) => Err(InkCompileError::Diagnostics(errors.clone())),
+            None => Err(InkCompileError::Backend(format!(
+                "No output for '{}'",
+                source.path
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let output = "\
+ERROR: 'main.ink' line 3: Unexpected token
+WARNING: line 7: Apparent loose end
+TODO: 'chapter.ink' line 12: write this scene
+Some other output";
+        let diagnostics = parse_diagnostics(output);
+        assert_eq!(
+            diagnostics,
+            vec![
+                InkDiagnostic {
+                    severity: InkDiagnosticSeverity::Error,
+                    file: Some("main.ink".to_string()),
+                    line: Some(3),
+                    message: "Unexpected token".to_string(),
+                },
+                InkDiagnostic {
+                    severity: InkDiagnosticSeverity::Warning,
+                    file: None,
+                    line: Some(7),
+                    message: "Apparent loose end".to_string(),
+                },
+                InkDiagnostic {
+                    severity: InkDiagnosticSeverity::Todo,
+                    file: Some("chapter.ink".to_string()),
+                    line: Some(12),
+                    message: "write this scene".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_diagnostic_without_location() {
+        let diagnostics = parse_diagnostics("ERROR: Root file not found");
+        assert_eq!(
+            diagnostics,
+            vec![InkDiagnostic::error("Root file not found")]
+        );
+    }
+
+    #[test]
+    fn test_missing_inklecate_is_backend_error() {
+        let compiler = InklecateCompiler::new().with_executable("/nonexistent/inklecate");
+        let result = compiler.compile(&InkSource {
+            path: "story.ink".to_string(),
+            text: "Hello".to_string(),
+            includes: HashMap::new(),
+        });
+        assert!(matches!(result, Err(InkCompileError::Backend(_))));
+    }
+}
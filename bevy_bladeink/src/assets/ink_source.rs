@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use bevy::{
+    asset::{AssetLoader, AsyncReadExt, LoadContext, ParseAssetPathError, ReadAssetBytesError},
+    platform::collections::HashSet,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    assets::{
+        InkCompileError, InkCompiler, InkDiagnosticSeverity, InkSource, InkStoryJsonLoaderError,
+        StoryJson,
+    },
+    events::InkLoadStage,
+};
+
+/// Settings of the [`InkSourceLoader`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InkSourceLoaderSettings {
+    /// Fails to load the story if the compiler reports any warning.
+    pub warnings_as_errors: bool,
+}
+
+/// Possible errors that can be produced by [`InkSourceLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum InkSourceLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    /// An [UTF8 Error](std::str::Utf8Error)
+    #[error("Could not convert bytes to string: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    /// A file included by the story has an invalid path.
+    #[error("Invalid include path '{path}': {source}")]
+    InvalidInclude {
+        path: String,
+        source: ParseAssetPathError,
+    },
+    /// A file included by the story could not be read.
+    #[error("Could not read included file '{path}': {source}")]
+    Include {
+        path: String,
+        source: ReadAssetBytesError,
+    },
+    /// The story does not compile.
+    #[error("Could not compile the story: {0}")]
+    Compile(#[from] InkCompileError),
+    /// The compiled story is invalid.
+    #[error("{0}")]
+    Json(#[from] InkStoryJsonLoaderError),
+}
+
+impl InkSourceLoaderError {
+    /// The step of loading the story which failed.
+    pub(crate) fn stage(&self) -> InkLoadStage {
+        match self {
+            InkSourceLoaderError::Io(_)
+            | InkSourceLoaderError::Utf8(_)
+            | InkSourceLoaderError::InvalidInclude { .. }
+            | InkSourceLoaderError::Include { .. } => InkLoadStage::AssetIo,
+            InkSourceLoaderError::Compile(_) => InkLoadStage::Compile,
+            InkSourceLoaderError::Json(err) => err.stage(),
+        }
+    }
+}
+
+/// `InkSourceLoader` compiles `.ink` files into a story. Files pulled in with
+/// `INCLUDE` are dependencies of the story, so it is reloaded when any of them
+/// changes.
+pub struct InkSourceLoader {
+    compiler: Arc<dyn InkCompiler>,
+}
+
+impl InkSourceLoader {
+    pub fn new(compiler: impl InkCompiler) -> Self {
+        Self {
+            compiler: Arc::new(compiler),
+        }
+    }
+}
+
+impl AssetLoader for InkSourceLoader {
+    type Asset = StoryJson;
+    type Settings = InkSourceLoaderSettings;
+    type Error = InkSourceLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        let mut source = InkSource {
+            path: load_context
+                .asset_path()
+                .path()
+                .to_string_lossy()
+                .into_owned(),
+            text: strip_bom(text),
+            ..default()
+        };
+
+        // ink resolves every include relative to the root file, nested or not
+        let mut pending = parse_includes(&source.text);
+        let mut seen: HashSet<String> = pending.iter().cloned().collect();
+        while let Some(include) = pending.pop() {
+            let path = load_context
+                .asset_path()
+                .resolve_embed(&include)
+                .map_err(|source| InkSourceLoaderError::InvalidInclude {
+                    path: include.clone(),
+                    source,
+                })?;
+            let bytes = load_context
+                .read_asset_bytes(path)
+                .await
+                .map_err(|source| InkSourceLoaderError::Include {
+                    path: include.clone(),
+                    source,
+                })?;
+            let text = strip_bom(std::str::from_utf8(&bytes)?.to_string());
+            for nested in parse_includes(&text) {
+                if seen.insert(nested.clone()) {
+                    pending.push(nested);
+                }
+            }
+            source.includes.insert(include, text);
+        }
+
+        let compiled = self.compiler.compile(&source)?;
+        let warnings: Vec<_> = compiled
+            .diagnostics
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == InkDiagnosticSeverity::Warning)
+            .collect();
+        if settings.warnings_as_errors && !warnings.is_empty() {
+            return Err(InkCompileError::Diagnostics(warnings).into());
+        }
+        for warning in warnings {
+            warn!("{}: {warning}", source.path);
+        }
+        Ok(StoryJson::from_json(strip_bom(compiled.json), true)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ink"]
+    }
+}
+
+fn strip_bom(text: String) -> String {
+    match text.strip_prefix('\u{feff}') {
+        Some(text) => text.to_string(),
+        None => text,
+    }
+}
+
+/// Returns the paths of the files included by the ink source.
+fn parse_includes(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix("INCLUDE "))
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Allows compiling `.ink` files with the application.
+pub trait AddInkCompilerApp {
+    /// Loads `.ink` files through the given compiler. Must be called after
+    /// adding the `InkPlugin`.
+    fn use_ink_compiler(&mut self, compiler: impl InkCompiler) -> &mut Self;
+}
+
+impl AddInkCompilerApp for App {
+    fn use_ink_compiler(&mut self, compiler: impl InkCompiler) -> &mut Self {
+        self.register_asset_loader(InkSourceLoader::new(compiler))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_includes() {
+        let text = "\
+INCLUDE characters.ink
+  INCLUDE chapters/one.ink
+// INCLUDE commented.ink
+Hello INCLUDE inline.ink
+INCLUDE ";
+        assert_eq!(
+            parse_includes(text),
+            vec!["characters.ink", "chapters/one.ink"]
+        );
+    }
+}
@@ -1,5 +1,11 @@
+mod compiler;
+mod ink_source;
 mod save_text;
 mod story_json;
+mod story_metadata;
 
+pub use compiler::*;
+pub use ink_source::*;
 // pub use save_text::*;
 pub use story_json::*;
+pub use story_metadata::*;
@@ -2,17 +2,71 @@ use bevy::{
     asset::{AssetLoader, AsyncReadExt, LoadContext},
     prelude::*,
 };
+use bladeink::story::{INK_VERSION_CURRENT, INK_VERSION_MINIMUM_COMPATIBLE};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 
+use crate::{assets::InkStoryMetadata, events::InkLoadStage};
+
 /// `StoryJson` is a struct that represents a JSON file containing a story.
 #[derive(Asset, TypePath)]
 pub struct StoryJson {
     pub(crate) text: String,
+    metadata: InkStoryMetadata,
 }
 
 impl StoryJson {
+    /// Creates the story from its JSON, reading whatever metadata it can
+    /// without validating it.
     pub fn new(text: String) -> Self {
-        Self { text }
+        let metadata = serde_json::from_str(&text)
+            .map(|json| InkStoryMetadata::from_json(&json))
+            .unwrap_or_default();
+        Self { text, metadata }
+    }
+
+    /// Parses and validates the JSON of a story.
+    pub fn from_json(text: String, strict: bool) -> Result<Self, InkStoryJsonLoaderError> {
+        let json: Value = serde_json::from_str(&text)?;
+        let supported = i64::from(INK_VERSION_MINIMUM_COMPATIBLE)..=i64::from(INK_VERSION_CURRENT);
+        match json.get("inkVersion").and_then(Value::as_i64) {
+            Some(version) if strict && !supported.contains(&version) => {
+                return Err(InkStoryJsonLoaderError::UnsupportedInkVersion(version));
+            }
+            None if strict => return Err(InkStoryJsonLoaderError::MissingInkVersion),
+            _ => {}
+        }
+        if !json.get("root").is_some_and(Value::is_array) {
+            return Err(InkStoryJsonLoaderError::MissingRoot);
+        }
+        let metadata = InkStoryMetadata::from_json(&json);
+        Ok(Self { text, metadata })
+    }
+
+    /// The metadata read from the story.
+    pub fn metadata(&self) -> &InkStoryMetadata {
+        &self.metadata
+    }
+}
+
+/// Settings of the [`InkStoryJsonLoader`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InkStoryJsonLoaderSettings {
+    /// Removes the byte order mark some tools write at the start of the file.
+    /// Without it, files starting with one fail to parse.
+    pub strip_bom: bool,
+    /// Rejects stories compiled for an ink version the runtime doesn't
+    /// support, or which don't declare one.
+    pub strict: bool,
+}
+
+impl Default for InkStoryJsonLoaderSettings {
+    fn default() -> Self {
+        Self {
+            strip_bom: true,
+            strict: true,
+        }
     }
 }
 
@@ -26,6 +80,35 @@ pub enum InkStoryJsonLoaderError {
     /// An [UTF8 Error](std::str::Utf8Error)
     #[error("Could not convert bytes to string: {0}")]
     Utf8(#[from] std::str::Utf8Error),
+    /// The file is not valid JSON.
+    #[error("Could not parse the story JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The story doesn't declare the ink version it was compiled with.
+    #[error("The story doesn't declare an ink version")]
+    MissingInkVersion,
+    /// The story was compiled for an ink version the runtime doesn't support.
+    #[error(
+        "Unsupported ink version {0}, expected {INK_VERSION_MINIMUM_COMPATIBLE} to {INK_VERSION_CURRENT}"
+    )]
+    UnsupportedInkVersion(i64),
+    /// The story doesn't have a root container.
+    #[error("The story doesn't have a root container")]
+    MissingRoot,
+}
+
+impl InkStoryJsonLoaderError {
+    /// The step of loading the story which failed.
+    pub(crate) fn stage(&self) -> InkLoadStage {
+        match self {
+            InkStoryJsonLoaderError::Io(_) | InkStoryJsonLoaderError::Utf8(_) => {
+                InkLoadStage::AssetIo
+            }
+            InkStoryJsonLoaderError::Json(_)
+            | InkStoryJsonLoaderError::MissingInkVersion
+            | InkStoryJsonLoaderError::UnsupportedInkVersion(_)
+            | InkStoryJsonLoaderError::MissingRoot => InkLoadStage::JsonParse,
+        }
+    }
 }
 
 /// `InkStoryJsonLoader` is a struct that loads a JSON file containing a story.
@@ -33,27 +116,76 @@ pub struct InkStoryJsonLoader;
 
 impl AssetLoader for InkStoryJsonLoader {
     type Asset = StoryJson;
-    type Settings = ();
+    type Settings = InkStoryJsonLoaderSettings;
     type Error = InkStoryJsonLoaderError;
 
     async fn load(
         &self,
         reader: &mut dyn bevy::asset::io::Reader,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
         _load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut text = String::new();
         reader.read_to_string(&mut text).await?;
-        // not sure why, but the npx inkjs CLI sometimes inserts a single NBSP
-        // byte at the beginning of the file. i could investigate, but this is
-        // an easy workaround for now.
-        if !text.starts_with("{") {
-            text.remove(0);
+        // inklecate and the npx inkjs CLI write a byte order mark at the
+        // beginning of the file
+        if settings.strip_bom
+            && let Some(stripped) = text.strip_prefix('\u{feff}')
+        {
+            text = stripped.to_string();
         }
-        Ok(StoryJson::new(text))
+        StoryJson::from_json(text, settings.strict)
     }
 
     fn extensions(&self) -> &[&str] {
         &[".ink.json"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_story_parses() {
+        let text =
+            r#"{"inkVersion":21,"root":[["^Hello","\n","done",null],"done",null],"listDefs":{}}"#;
+        let story = StoryJson::from_json(text.to_string(), true).unwrap();
+        assert_eq!(story.metadata().ink_version, 21);
+    }
+
+    #[test]
+    fn test_invalid_json_is_rejected() {
+        let result = StoryJson::from_json("{\"inkVersion\":21,".to_string(), true);
+        assert!(matches!(result, Err(InkStoryJsonLoaderError::Json(_))));
+
+        // without stripping it, the byte order mark is invalid JSON
+        let result = StoryJson::from_json("\u{feff}{}".to_string(), false);
+        assert!(matches!(result, Err(InkStoryJsonLoaderError::Json(_))));
+    }
+
+    #[test]
+    fn test_ink_version_is_validated() {
+        let text = r#"{"inkVersion":99,"root":[]}"#;
+        let result = StoryJson::from_json(text.to_string(), true);
+        assert!(matches!(
+            result,
+            Err(InkStoryJsonLoaderError::UnsupportedInkVersion(99))
+        ));
+        assert!(StoryJson::from_json(text.to_string(), false).is_ok());
+
+        let text = r#"{"root":[]}"#;
+        let result = StoryJson::from_json(text.to_string(), true);
+        assert!(matches!(
+            result,
+            Err(InkStoryJsonLoaderError::MissingInkVersion)
+        ));
+        assert!(StoryJson::from_json(text.to_string(), false).is_ok());
+    }
+
+    #[test]
+    fn test_root_container_is_required() {
+        let result = StoryJson::from_json(r#"{"inkVersion":21}"#.to_string(), false);
+        assert!(matches!(result, Err(InkStoryJsonLoaderError::MissingRoot)));
+    }
+}
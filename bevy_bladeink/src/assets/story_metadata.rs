@@ -0,0 +1,205 @@
+use bevy::platform::collections::HashMap;
+use serde_json::Value;
+
+/// Metadata read from the JSON of a story while loading it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InkStoryMetadata {
+    /// The ink format version the story was compiled with.
+    pub ink_version: i32,
+    /// The knots of the story, functions included.
+    pub knots: Vec<String>,
+    /// The stitches of the story, as `knot.stitch` paths.
+    pub stitches: Vec<String>,
+    /// The global variables declared with `VAR`.
+    pub globals: Vec<String>,
+    /// The `EXTERNAL` functions the story calls, with the number of arguments
+    /// they are called with.
+    pub externals: HashMap<String, usize>,
+    /// The tags at the top of the story.
+    pub global_tags: Vec<String>,
+    /// The `LIST`s declared by the story, with the value of each of their
+    /// items.
+    pub lists: HashMap<String, HashMap<String, i32>>,
+}
+
+impl InkStoryMetadata {
+    /// Reads the metadata from the parsed JSON of a story.
+    pub(crate) fn from_json(json: &Value) -> Self {
+        let mut metadata = Self {
+            ink_version: json
+                .get("inkVersion")
+                .and_then(Value::as_i64)
+                .and_then(|version| i32::try_from(version).ok())
+                .unwrap_or_default(),
+            ..Self::default()
+        };
+        metadata.lists = list_definitions(json);
+        let Some(root) = json.get("root").and_then(Value::as_array) else {
+            return metadata;
+        };
+
+        if let Some(named) = named_content(root) {
+            for (name, content) in named {
+                if name == "global decl" {
+                    collect_globals(content, &mut metadata.globals);
+                    continue;
+                }
+                metadata.knots.push(name.clone());
+                let stitches = content
+                    .as_array()
+                    .and_then(|knot| named_content(knot))
+                    .into_iter()
+                    .flatten()
+                    .map(|(stitch, _)| stitch)
+                    .filter(|stitch| is_user_named(stitch))
+                    .map(|stitch| format!("{name}.{stitch}"));
+                metadata.stitches.extend(stitches);
+            }
+        }
+        for value in root {
+            collect_externals(value, &mut metadata.externals);
+        }
+        metadata.global_tags = global_tags(root);
+
+        metadata.knots.sort();
+        metadata.stitches.sort();
+        metadata.globals.sort();
+        metadata
+    }
+}
+
+fn list_definitions(json: &Value) -> HashMap<String, HashMap<String, i32>> {
+    let Some(lists) = json.get("listDefs").and_then(Value::as_object) else {
+        return HashMap::default();
+    };
+    lists
+        .iter()
+        .map(|(name, items)| {
+            let items = items
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(item, value)| {
+                    let value = i32::try_from(value.as_i64()?).ok()?;
+                    Some((item.clone(), value))
+                })
+                .collect();
+            (name.clone(), items)
+        })
+        .collect()
+}
+
+/// The named content of a container, stored in the object ending it.
+fn named_content(container: &[Value]) -> Option<impl Iterator<Item = (&String, &Value)>> {
+    let named = container.last()?.as_object()?;
+    Some(named.iter().filter(|(name, _)| !name.starts_with('#')))
+}
+
+/// Names generated by the compiler for gathers, choices and the like.
+fn is_user_named(name: &str) -> bool {
+    let generated = name.starts_with('$')
+        || name.chars().all(|c| c.is_ascii_digit())
+        || ["c-", "g-", "s-"].iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|index| index.chars().all(|c| c.is_ascii_digit()))
+        });
+    !generated
+}
+
+fn collect_globals(content: &Value, globals: &mut Vec<String>) {
+    let Some(content) = content.as_array() else {
+        return;
+    };
+    let declared = content
+        .iter()
+        .filter_map(Value::as_object)
+        .filter_map(|object| object.get("VAR="))
+        .filter_map(Value::as_str)
+        .map(str::to_string);
+    globals.extend(declared);
+}
+
+fn collect_externals(value: &Value, externals: &mut HashMap<String, usize>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_externals(value, externals);
+            }
+        }
+        Value::Object(object) => {
+            if let Some(name) = object.get("x()").and_then(Value::as_str) {
+                let args = object
+                    .get("exArgs")
+                    .and_then(Value::as_u64)
+                    .and_then(|args| usize::try_from(args).ok())
+                    .unwrap_or_default();
+                externals.insert(name.to_string(), args);
+            }
+            for value in object.values() {
+                collect_externals(value, externals);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The tags before the first content of the story, in either the current
+/// `"#", "^tag", "/#"` format, or the legacy `{"#": "tag"}` one.
+fn global_tags(root: &[Value]) -> Vec<String> {
+    let mut content = root;
+    // the first content of the story may be nested in containers
+    while let Some(Value::Array(first)) = content.first() {
+        content = first;
+    }
+
+    let mut tags = Vec::new();
+    let mut tag: Option<String> = None;
+    for value in content {
+        match (value, &mut tag) {
+            (Value::String(s), None) if s == "#" => tag = Some(String::new()),
+            (Value::String(s), Some(text)) if s == "/#" => {
+                tags.push(text.trim().to_string());
+                tag = None;
+            }
+            (Value::String(s), Some(text)) => {
+                text.push_str(s.strip_prefix('^').unwrap_or(s));
+            }
+            (Value::Object(object), None) if object.contains_key("#") => {
+                if let Some(text) = object.get("#").and_then(Value::as_str) {
+                    tags.push(text.trim().to_string());
+                }
+            }
+            (_, None) => break,
+            (_, Some(_)) => {}
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_of_story() {
+        let json: Value = serde_json::from_str(
+            r##"{"inkVersion":21,"root":[["#","^author: Syynth","/#",{"#":"chapter: 1"},"^Hello","\n",["done",{"#n":"g-0"}],null],"done",{
+                "market":[["^Busy.","\n",null],{"stall":["^Apples.","\n",{"x()":"buy","exArgs":2},null],"c-0":["done",null],"g-1":["done",null],"#f":1}],
+                "start":["ev",{"x()":"play_sound","exArgs":1},"pop","/ev","done",null],
+                "global decl":["ev",0,{"VAR=":"gold"},"str","^","/str",{"VAR=":"name"},"/ev","end",null]
+            }],"listDefs":{"Mood":{"calm":1,"angry":2}}}"##,
+        )
+        .unwrap();
+        let metadata = InkStoryMetadata::from_json(&json);
+
+        assert_eq!(metadata.ink_version, 21);
+        assert_eq!(metadata.knots, vec!["market", "start"]);
+        assert_eq!(metadata.stitches, vec!["market.stall"]);
+        assert_eq!(metadata.globals, vec!["gold", "name"]);
+        assert_eq!(metadata.externals.len(), 2);
+        assert_eq!(metadata.externals["buy"], 2);
+        assert_eq!(metadata.externals["play_sound"], 1);
+        assert_eq!(metadata.global_tags, vec!["author: Syynth", "chapter: 1"]);
+        assert_eq!(metadata.lists["Mood"]["angry"], 2);
+    }
+}
@@ -1,36 +1,99 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
 
-use crate::events::SequenceBegin;
+use crate::{
+    commands::InkCommandQueue,
+    components::InkPath,
+    events::SequenceBegin,
+    resources::{InkStories, InkStoryId},
+    runtime::{InkEffects, run_ink},
+};
 
 /// Represents a command to start an ink sequence.
 pub(crate) struct BeginSequenceCommand {
+    story: InkStoryId,
+    flow: Option<String>,
     sequence: String,
+    speaker: Option<Entity>,
 }
 
 impl BeginSequenceCommand {
     /// Creates a new `BeginSequenceCommand` with the given sequence.
-    pub(crate) fn path(sequence: impl Into<String>) -> Self {
+    pub(crate) fn path(story: InkStoryId, sequence: impl Into<String>) -> Self {
         BeginSequenceCommand {
+            story,
+            flow: None,
             sequence: sequence.into(),
+            speaker: None,
         }
     }
+
+    /// Creates a new `BeginSequenceCommand` from an entity's [`InkPath`].
+    pub(crate) fn from_ink_path(speaker: Entity, ink_path: &InkPath) -> Self {
+        BeginSequenceCommand {
+            story: ink_path.story,
+            flow: ink_path.flow.clone(),
+            sequence: ink_path.path.clone(),
+            speaker: Some(speaker),
+        }
+    }
+
+    /// Switches to the given flow before starting the sequence.
+    pub(crate) fn in_flow(mut self, flow: impl Into<String>) -> Self {
+        self.flow = Some(flow.into());
+        self
+    }
 }
 
 impl Command for BeginSequenceCommand {
     fn apply(self, world: &mut World) {
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            self.begin(stories, &mut effects);
+            effects
+        });
+    }
+}
+
+impl BeginSequenceCommand {
+    fn begin(self, stories: &mut InkStories, effects: &mut InkEffects) {
         #[cfg(feature = "debug_log")]
         info!("Starting ink sequence '{}'", self.sequence);
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
+        let Some((story, flows)) = stories.get_with_flows_mut(self.story) else {
             error!(
-                "Failed to start ink sequence '{}': Story resource not found. Did you forget to insert the InkProject resource?",
-                self.sequence
+                "Failed to start ink sequence '{}': Story {:?} not found. Did you forget to insert the InkProject resource?",
+                self.sequence, self.story
             );
             return;
         };
+        if let Some(flow) = &self.flow {
+            if let Err(err) = story.switch_flow(flow) {
+                warn!(
+                    "Failed to start ink sequence '{}': could not switch to flow '{}': {}",
+                    self.sequence, flow, err
+                );
+                return;
+            }
+            flows.switch(Some(flow));
+        }
+        let flow = flows.current.clone();
         match story.choose_path_string(&self.sequence, true, None) {
             Ok(_) => {
-                world.trigger(SequenceBegin(self.sequence));
+                stories.set_speaker(self.story, flow.clone(), self.speaker);
+                // starting over in the suspended flow abandons the blocking call
+                if stories
+                    .suspension(self.story)
+                    .is_some_and(|suspension| suspension.flow == flow)
+                {
+                    stories.clear_suspension(self.story);
+                }
+                effects.trigger_sequence(
+                    self.speaker,
+                    SequenceBegin {
+                        story: self.story,
+                        flow,
+                        path: self.sequence,
+                    },
+                );
             }
             Err(err) => {
                 warn!("Failed to start ink sequence '{}': {}", self.sequence, err);
@@ -59,11 +122,54 @@ pub trait BeginSequenceCommandsExt {
     /// ...because of the way that content is nested within a weave
     /// structure.
     fn ink_begin_sequence(&mut self, sequence: impl Into<String>) -> &mut Self;
+
+    /// Switches to the named flow, then begins the sequence in it. Other flows
+    /// keep their position, and can be resumed by switching back to them.
+    fn ink_begin_sequence_in_flow(
+        &mut self,
+        flow: impl Into<String>,
+        sequence: impl Into<String>,
+    ) -> &mut Self;
 }
 
-impl<'w, 's> BeginSequenceCommandsExt for Commands<'w, 's> {
+impl<T: InkCommandQueue> BeginSequenceCommandsExt for T {
     fn ink_begin_sequence(&mut self, sequence: impl Into<String>) -> &mut Self {
-        self.queue(BeginSequenceCommand::path(sequence));
+        let story = self.ink_story_id();
+        self.queue_ink_command(BeginSequenceCommand::path(story, sequence));
+        self
+    }
+
+    fn ink_begin_sequence_in_flow(
+        &mut self,
+        flow: impl Into<String>,
+        sequence: impl Into<String>,
+    ) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(BeginSequenceCommand::path(story, sequence).in_flow(flow));
         self
     }
 }
+
+/// Helper trait for starting the dialogue of an entity with an [`InkPath`].
+pub trait InkPathCommandsExt {
+    /// Begins the sequence at the entity's [`InkPath`]. Until the sequence
+    /// ends, the sequence events are also triggered as [`InkEntityEvent`]s
+    /// targeting the entity.
+    ///
+    /// [`InkEntityEvent`]: crate::events::InkEntityEvent
+    fn ink_begin(&mut self) -> &mut Self;
+}
+
+impl InkPathCommandsExt for EntityCommands<'_> {
+    fn ink_begin(&mut self) -> &mut Self {
+        self.queue(|entity: EntityWorldMut| {
+            let speaker = entity.id();
+            let Some(ink_path) = entity.get::<InkPath>() else {
+                error!("Failed to begin ink sequence: {speaker} has no InkPath component");
+                return;
+            };
+            let command = BeginSequenceCommand::from_ink_path(speaker, ink_path);
+            command.apply(entity.into_world_mut());
+        })
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::{
+    commands::InkCommandQueue,
+    ink::{AddInkBindingApp, InkBindingDefinition, InkSystemOutput, InkValue},
+};
+
+/// Helper trait for changing the bindings of a running story through
+/// `Commands`. The bindings only apply to the story the commands target, and
+/// take precedence over the bindings for every story.
+pub trait InkBindingCommandsExt {
+    /// Binds the ink function to the event of the binding definition, see
+    /// [`AddInkBindingApp::bind_ink_function`].
+    fn ink_bind_function<T: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default;
+
+    /// Binds the ink function to a function which suspends the story, see
+    /// [`AddInkBindingApp::bind_ink_blocking_function`].
+    fn ink_bind_blocking_function<T: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default;
+
+    /// Binds the ink function to a system, see
+    /// [`AddInkBindingApp::bind_ink_system`].
+    fn ink_bind_system<E, Out, M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<In<E>, Out, M> + Send + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput;
+
+    /// Binds the ink function to a closure, see
+    /// [`AddInkBindingApp::bind_ink_fn`].
+    fn ink_bind_fn(
+        &mut self,
+        name: impl Into<String>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Removes the binding of the ink function from the story, which falls
+    /// back to the binding for every story, if any.
+    fn ink_unbind_function(&mut self, name: impl Into<String>) -> &mut Self;
+}
+
+impl<T: InkCommandQueue> InkBindingCommandsExt for T {
+    fn ink_bind_function<B: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self
+    where
+        for<'a> <B::Event as Event>::Trigger<'a>: Default,
+    {
+        let story = self.ink_story_id();
+        let name = name.into();
+        self.queue_ink_command(move |world: &mut World| {
+            world.bind_ink_function_for::<B>(story, name);
+        });
+        self
+    }
+
+    fn ink_bind_blocking_function<B: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self
+    where
+        for<'a> <B::Event as Event>::Trigger<'a>: Default,
+    {
+        let story = self.ink_story_id();
+        let name = name.into();
+        self.queue_ink_command(move |world: &mut World| {
+            world.bind_ink_blocking_function_for::<B>(story, name);
+        });
+        self
+    }
+
+    fn ink_bind_system<E, Out, M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<In<E>, Out, M> + Send + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput,
+    {
+        let story = self.ink_story_id();
+        let name = name.into();
+        self.queue_ink_command(move |world: &mut World| {
+            world.bind_ink_system_for(story, name, system);
+        });
+        self
+    }
+
+    fn ink_bind_fn(
+        &mut self,
+        name: impl Into<String>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self {
+        let story = self.ink_story_id();
+        let name = name.into();
+        self.queue_ink_command(move |world: &mut World| {
+            world.bind_ink_fn_for(story, name, function);
+        });
+        self
+    }
+
+    fn ink_unbind_function(&mut self, name: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        let name = name.into();
+        self.queue_ink_command(move |world: &mut World| {
+            world.unbind_ink_function_for(story, name);
+        });
+        self
+    }
+}
@@ -1,73 +1,173 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
 
 use crate::{
-    events::{DeliverChoices, DeliverLine, InkStateChanged, SequenceEnd},
-    ink::ChoiceItem,
+    commands::InkCommandQueue,
+    events::{DeliverChoices, DeliverLine, SequenceEnd, SequenceSuspended},
+    ink::{ChoiceItem, with_blocking_calls},
+    resources::{InkStories, InkStoryId},
+    runtime::{InkEffects, collect_binding_events, run_ink},
 };
 
 /// Represents a command to continue an ink sequence.
 #[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct ContinueSequenceCommand;
+pub(crate) struct ContinueSequenceCommand {
+    story: InkStoryId,
+}
 
 impl ContinueSequenceCommand {
     /// Creates a new `ContinueSequenceCommand`.
-    pub(crate) fn new() -> Self {
-        ContinueSequenceCommand
+    pub(crate) fn new(story: InkStoryId) -> Self {
+        ContinueSequenceCommand { story }
     }
 }
 
 impl Command for ContinueSequenceCommand {
     fn apply(self, world: &mut World) {
-        #[cfg(feature = "debug_log")]
-        info!("Continuing ink sequence");
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
-            error!(
-                "Failed to load state: Story resource not found. Did you forget to insert the InkProject resource?",
-            );
-            return;
-        };
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            continue_story(stories, self.story, &mut effects);
+            effects
+        });
+    }
+}
 
-        if !story.can_continue() {
+/// Advances the story to its next line or set of choices, ending the sequence
+/// if neither is available.
+pub(crate) fn continue_story(stories: &mut InkStories, id: InkStoryId, effects: &mut InkEffects) {
+    #[cfg(feature = "debug_log")]
+    info!("Continuing ink sequence");
+    let (resolved, replayed) = match stories.suspension(id) {
+        Some(suspension) if !suspension.resumed => {
             #[cfg(feature = "debug_log")]
-            info!("Continuing: No more content available");
-            let choices = story.get_current_choices();
+            info!("Continuing: Story is suspended");
+            let speaker = stories.speaker(id, suspension.flow.as_deref());
+            effects.trigger_sequence(
+                speaker,
+                SequenceSuspended {
+                    story: id,
+                    flow: suspension.flow.clone(),
+                    function: suspension.function.clone(),
+                    token: suspension.token,
+                },
+            );
+            return;
+        }
+        Some(suspension) => (suspension.resolved.clone(), suspension.replayed.clone()),
+        None => (Vec::new(), Vec::new()),
+    };
+    let blocking = stories.has_blocking_bindings(id);
+    let Some((story, flows)) = stories.get_with_flows_mut(id) else {
+        error!(
+            "Failed to continue sequence: Story {:?} not found. Did you forget to insert the InkProject resource?",
+            id
+        );
+        return;
+    };
+    let flow = flows.current.clone();
 
-            let choices: Vec<ChoiceItem> = choices.iter().map(|c| c.as_ref().into()).collect();
+    if !story.can_continue() {
+        #[cfg(feature = "debug_log")]
+        info!("Continuing: No more content available");
+        let choices = story.get_current_choices();
 
-            if choices.is_empty() {
-                #[cfg(feature = "debug_log")]
-                info!("Continuing: No choices available");
-                world.trigger(SequenceEnd);
-                return;
-            }
+        let choices: Vec<ChoiceItem> = choices.iter().map(|c| c.as_ref().into()).collect();
+        let speaker = stories.speaker(id, flow.as_deref());
 
+        if choices.is_empty() {
             #[cfg(feature = "debug_log")]
-            info!("Continuing: Delivering {} choices", choices.len());
-
-            world.trigger(DeliverChoices::new(choices));
-            world.trigger(InkStateChanged);
+            info!("Continuing: No choices available");
+            effects.trigger_sequence(
+                speaker,
+                SequenceEnd {
+                    story: id,
+                    flow: flow.clone(),
+                },
+            );
+            stories.set_speaker(id, flow, None);
             return;
         }
 
-        match story.cont() {
-            Ok(text) => {
-                let tags = story.get_current_tags().unwrap_or_default();
+        #[cfg(feature = "debug_log")]
+        info!("Continuing: Delivering {} choices", choices.len());
 
-                #[cfg(feature = "debug_log")]
-                {
-                    info!("Continuing: Delivering line - {}", text);
-                    info!("Continuing: Tags - {:?}", tags);
-                }
-                world.trigger(DeliverLine::new(text, tags));
-            }
+        effects.trigger_sequence(speaker, DeliverChoices::new(id, flow, choices));
+        effects.update_state(stories, id);
+        return;
+    }
+
+    // blocking bindings roll the story back to before the line, so it can be
+    // continued again with their return value once resumed
+    let snapshot = if blocking {
+        match story.save_state() {
+            Ok(snapshot) => Some(snapshot),
             Err(err) => {
-                error!("Error continuing story: {}", err);
+                error!("Failed to continue story: could not save its state before the line: {err}");
                 return;
             }
+        }
+    } else {
+        None
+    };
+    let ((line, binding_events), suspended) = with_blocking_calls(&resolved, &replayed, || {
+        collect_binding_events(|| {
+            story
+                .cont()
+                .map(|text| (text, story.get_current_tags().unwrap_or_default()))
+        })
+    });
+    if let Some(call) = suspended {
+        // the other bindings on the line trigger their events once it is
+        // continued again, only the blocking one is triggered now
+        let Some(snapshot) = snapshot else {
+            error!("Failed to suspend story: no state to roll back to");
+            return;
         };
-        world.trigger(InkStateChanged);
+        if let Err(err) = story.load_state(&snapshot) {
+            error!("Failed to suspend story: {err}");
+            return;
+        }
+        #[cfg(feature = "debug_log")]
+        info!("Continuing: Suspended on '{}'", call.function);
+        let token = stories.suspend(
+            id,
+            flow.clone(),
+            call.function.clone(),
+            resolved,
+            call.replayed,
+        );
+        let speaker = stories.speaker(id, flow.as_deref());
+        effects.append(call.event);
+        effects.trigger_sequence(
+            speaker,
+            SequenceSuspended {
+                story: id,
+                flow,
+                function: call.function,
+                token,
+            },
+        );
+        return;
     }
+    // events of bindings called while producing the line precede it
+    effects.append(binding_events);
+    stories.clear_suspension(id);
+
+    match line {
+        Ok((text, tags)) => {
+            #[cfg(feature = "debug_log")]
+            {
+                info!("Continuing: Delivering line - {}", text);
+                info!("Continuing: Tags - {:?}", tags);
+            }
+            let speaker = stories.speaker(id, flow.as_deref());
+            effects.trigger_sequence(speaker, DeliverLine::new(id, flow, text, tags));
+        }
+        Err(err) => {
+            error!("Error continuing story: {}", err);
+            return;
+        }
+    };
+    effects.update_state(stories, id);
 }
 
 /// Helper trait for adding `ContinueSequenceCommand` to a `Commands` instance.
@@ -76,9 +176,10 @@ pub trait ContinueSequenceCommandsExt {
     fn ink_continue_sequence(&mut self) -> &mut Self;
 }
 
-impl<'w, 's> ContinueSequenceCommandsExt for Commands<'w, 's> {
+impl<T: InkCommandQueue> ContinueSequenceCommandsExt for T {
     fn ink_continue_sequence(&mut self) -> &mut Self {
-        self.queue(ContinueSequenceCommand::new());
+        let story = self.ink_story_id();
+        self.queue_ink_command(ContinueSequenceCommand::new(story));
         self
     }
 }
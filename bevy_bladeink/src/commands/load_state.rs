@@ -1,36 +1,94 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
 
-use crate::ink::InkState;
+use crate::{
+    commands::{InkCommandQueue, refresh_tracked_variables},
+    events::StateLoadFailed,
+    ink::{InkState, InkStateError},
+    resources::{InkStateMigration, InkStateMigrations, InkStories, InkStoryId},
+    runtime::{InkEffects, run_ink},
+};
 
 /// Represents a command to load the ink story state, and variables.
 pub(crate) struct LoadStateCommand {
+    story: InkStoryId,
     state: InkState,
 }
 
 impl LoadStateCommand {
     /// Creates a new `LoadStateCommand` with the given state.
-    pub(crate) fn new(state: InkState) -> Self {
-        LoadStateCommand { state }
+    pub(crate) fn new(story: InkStoryId, state: InkState) -> Self {
+        LoadStateCommand { story, state }
     }
 }
 
 impl Command for LoadStateCommand {
     fn apply(self, world: &mut World) {
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
-            error!(
-                "Failed to load state: Story resource not found. Did you forget to insert the InkProject resource?",
-            );
-            return;
-        };
-        match story.load_state(&self.state.serialized_state) {
-            Ok(_) => (),
-            Err(err) => {
-                warn!("Failed to load state: {err}");
-                warn!("- contents: {:?}", self.state);
+        let migration = world
+            .get_resource::<InkStateMigrations>()
+            .and_then(|migrations| migrations.get(self.story))
+            .cloned();
+
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            if !stories.contains(self.story) {
+                error!(
+                    "Failed to load state: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return effects;
             }
+            if let Err(error) = load_state(stories, self.story, self.state, migration, &mut effects)
+            {
+                error!("Failed to load state: {error}");
+                effects.trigger(StateLoadFailed {
+                    story: self.story,
+                    error,
+                });
+            }
+            effects
+        });
+    }
+}
+
+/// Loads the state into the story, migrating it first if it was saved from
+/// another version of the story, and reports the values of the tracked
+/// variables in the loaded state.
+pub(crate) fn load_state(
+    stories: &mut InkStories,
+    id: InkStoryId,
+    mut state: InkState,
+    migration: Option<InkStateMigration>,
+    effects: &mut InkEffects,
+) -> Result<(), InkStateError> {
+    migrate_state(&mut state, stories.story_hash(id), migration)?;
+    let Some((story, flows)) = stories.get_with_flows_mut(id) else {
+        return Ok(());
+    };
+    story.load_state(&state.serialized_state)?;
+    *flows = state.flows;
+    stories.clear_speakers(id);
+    stories.clear_suspension(id);
+    refresh_tracked_variables(stories, id, effects);
+    Ok(())
+}
+
+/// Migrates the state if it was saved from another version of the story than
+/// the one hashing to `current`.
+pub(crate) fn migrate_state(
+    state: &mut InkState,
+    current: Option<u64>,
+    migration: Option<InkStateMigration>,
+) -> Result<(), InkStateError> {
+    if let (Some(saved), Some(current)) = (state.story_hash, current)
+        && saved != current
+    {
+        let Some(migration) = migration else {
+            return Err(InkStateError::StoryMismatch { saved, current });
         };
+        migration(state)?;
+        state.story_hash = Some(current);
     }
+    Ok(())
 }
 
 /// Helper trait for adding `LoadStateCommand` to `Commands`.
@@ -38,9 +96,10 @@ pub trait LoadStateCommandsExt {
     fn ink_load_state(&mut self, state: InkState) -> &mut Self;
 }
 
-impl LoadStateCommandsExt for Commands<'_, '_> {
+impl<T: InkCommandQueue> LoadStateCommandsExt for T {
     fn ink_load_state(&mut self, state: InkState) -> &mut Self {
-        self.queue(LoadStateCommand::new(state));
+        let story = self.ink_story_id();
+        self.queue_ink_command(LoadStateCommand::new(story, state));
         self
     }
 }
@@ -1,15 +1,25 @@
 mod begin_sequence;
+mod bindings;
 mod continue_sequence;
 mod load_state;
+mod remove_flow;
 mod reset_state;
+mod resume;
 mod select_choice;
 mod set_variable;
+mod story;
+mod switch_flow;
 mod track_variable;
 
 pub use begin_sequence::*;
+pub use bindings::*;
 pub use continue_sequence::*;
 pub use load_state::*;
+pub use remove_flow::*;
 pub use reset_state::*;
+pub use resume::*;
 pub use select_choice::*;
 pub use set_variable::*;
+pub use story::*;
+pub use switch_flow::*;
 pub use track_variable::*;
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use crate::{
+    commands::InkCommandQueue,
+    resources::InkStoryId,
+    runtime::{InkEffects, run_ink},
+};
+
+/// Represents a command to remove a named flow from an ink story.
+pub(crate) struct RemoveFlowCommand {
+    story: InkStoryId,
+    flow: String,
+}
+
+impl RemoveFlowCommand {
+    /// Creates a new `RemoveFlowCommand` for the given flow.
+    pub(crate) fn new(story: InkStoryId, flow: String) -> Self {
+        RemoveFlowCommand { story, flow }
+    }
+}
+
+impl Command for RemoveFlowCommand {
+    fn apply(self, world: &mut World) {
+        run_ink(world, move |stories| {
+            let Some((story, flows)) = stories.get_with_flows_mut(self.story) else {
+                error!(
+                    "Failed to remove flow: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return InkEffects::default();
+            };
+            // bladeink panics when removing a flow which was never opened
+            if !flows.contains(&self.flow) {
+                warn!("Failed to remove flow '{}': flow is not open", self.flow);
+                return InkEffects::default();
+            }
+            match story.remove_flow(&self.flow) {
+                Ok(_) => {
+                    flows.remove(&self.flow);
+                    stories.set_speaker(self.story, Some(self.flow), None);
+                }
+                Err(err) => warn!("Failed to remove flow '{}': {err}", self.flow),
+            }
+            InkEffects::default()
+        });
+    }
+}
+
+/// Helper trait for adding `RemoveFlowCommand` to `Commands`.
+pub trait RemoveFlowCommandsExt {
+    /// Removes the named flow and its position. If it is the current flow, the
+    /// story switches back to the default flow.
+    fn ink_remove_flow(&mut self, flow: impl Into<String>) -> &mut Self;
+}
+
+impl<T: InkCommandQueue> RemoveFlowCommandsExt for T {
+    fn ink_remove_flow(&mut self, flow: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(RemoveFlowCommand::new(story, flow.into()));
+        self
+    }
+}
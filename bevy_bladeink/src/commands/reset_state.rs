@@ -1,31 +1,47 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
+
+use crate::{
+    commands::InkCommandQueue,
+    ink::InkFlows,
+    resources::InkStoryId,
+    runtime::{InkEffects, run_ink},
+};
 
 /// Represents a command to start an ink sequence.
 #[derive(Default)]
-pub(crate) struct ResetStateCommand;
+pub(crate) struct ResetStateCommand {
+    story: InkStoryId,
+}
 
 impl ResetStateCommand {
     /// Creates a new `ResetStateCommand` with the given state.
-    pub(crate) fn new() -> Self {
-        ResetStateCommand
+    pub(crate) fn new(story: InkStoryId) -> Self {
+        ResetStateCommand { story }
     }
 }
 
 impl Command for ResetStateCommand {
     fn apply(self, world: &mut World) {
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
-            error!(
-                "Failed to load state: Story resource not found. Did you forget to insert the InkProject resource?",
-            );
-            return;
-        };
-        match story.reset_state() {
-            Ok(_) => (),
-            Err(err) => {
-                warn!("Failed to reset state: {err}");
-            }
-        };
+        run_ink(world, move |stories| {
+            let Some((story, flows)) = stories.get_with_flows_mut(self.story) else {
+                error!(
+                    "Failed to reset state: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return InkEffects::default();
+            };
+            match story.reset_state() {
+                Ok(_) => {
+                    *flows = InkFlows::default();
+                    stories.clear_speakers(self.story);
+                    stories.clear_suspension(self.story);
+                }
+                Err(err) => {
+                    warn!("Failed to reset state: {err}");
+                }
+            };
+            InkEffects::default()
+        });
     }
 }
 
@@ -34,9 +50,10 @@ pub trait ResetStateCommandsExt {
     fn ink_reset_state(&mut self) -> &mut Self;
 }
 
-impl ResetStateCommandsExt for Commands<'_, '_> {
+impl<T: InkCommandQueue> ResetStateCommandsExt for T {
     fn ink_reset_state(&mut self) -> &mut Self {
-        self.queue(ResetStateCommand::new());
+        let story = self.ink_story_id();
+        self.queue_ink_command(ResetStateCommand::new(story));
         self
     }
 }
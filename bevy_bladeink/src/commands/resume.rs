@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::{
+    commands::{InkCommandQueue, continue_story},
+    ink::{InkResumeToken, InkValue},
+    runtime::{InkEffects, run_ink},
+};
+
+/// Represents a command to resume a story suspended by a blocking binding.
+pub(crate) struct ResumeCommand {
+    token: InkResumeToken,
+    value: Option<InkValue>,
+}
+
+impl ResumeCommand {
+    /// Creates a new `ResumeCommand`, the value is returned to ink from the
+    /// blocking binding.
+    pub(crate) fn new(token: InkResumeToken, value: Option<InkValue>) -> Self {
+        ResumeCommand { token, value }
+    }
+}
+
+impl Command for ResumeCommand {
+    fn apply(self, world: &mut World) {
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            let id = self.token.story;
+            let Some(suspension) = stories
+                .suspension_mut(id)
+                .filter(|suspension| suspension.token == self.token)
+            else {
+                warn!(
+                    "Failed to resume story {:?}: it is not waiting on {:?}",
+                    id, self.token
+                );
+                return effects;
+            };
+            let flow = suspension.flow.clone();
+
+            // carry on in the flow which was suspended, leaving the suspension
+            // as it was if that fails, so that it can be resumed again
+            let Some((story, flows)) = stories.get_with_flows_mut(id) else {
+                error!(
+                    "Failed to resume story: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    id
+                );
+                return effects;
+            };
+            if flows.current != flow {
+                match &flow {
+                    Some(flow) => {
+                        if let Err(err) = story.switch_flow(flow) {
+                            warn!(
+                                "Failed to resume story: could not switch to flow '{flow}': {err}"
+                            );
+                            return effects;
+                        }
+                    }
+                    None => story.switch_to_default_flow(),
+                }
+                flows.switch(flow.as_deref());
+            }
+            if let Some(suspension) = stories.suspension_mut(id) {
+                suspension.resolved.push(self.value);
+                suspension.resumed = true;
+            }
+
+            continue_story(stories, id, &mut effects);
+            effects
+        });
+    }
+}
+
+/// Helper trait for adding `ResumeCommand` to `Commands`.
+pub trait ResumeCommandsExt {
+    /// Resumes the story suspended with the token, continuing the sequence.
+    fn ink_resume(&mut self, token: InkResumeToken) -> &mut Self;
+
+    /// Resumes the story suspended with the token, returning the value to ink
+    /// from the blocking binding.
+    fn ink_resume_with(&mut self, token: InkResumeToken, value: impl Into<InkValue>) -> &mut Self;
+}
+
+impl<T: InkCommandQueue> ResumeCommandsExt for T {
+    fn ink_resume(&mut self, token: InkResumeToken) -> &mut Self {
+        self.queue_ink_command(ResumeCommand::new(token, None));
+        self
+    }
+
+    fn ink_resume_with(&mut self, token: InkResumeToken, value: impl Into<InkValue>) -> &mut Self {
+        self.queue_ink_command(ResumeCommand::new(token, Some(value.into())));
+        self
+    }
+}
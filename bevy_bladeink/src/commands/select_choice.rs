@@ -1,45 +1,55 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
 
-use crate::prelude::ContinueSequenceCommandsExt;
+use crate::{
+    commands::{InkCommandQueue, continue_story},
+    resources::InkStoryId,
+    runtime::{InkEffects, run_ink},
+};
 
 /// Represents a command to continue an ink sequence.
 #[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct SelectChoiceCommand(usize);
+pub(crate) struct SelectChoiceCommand {
+    story: InkStoryId,
+    choice_index: usize,
+}
 
 impl SelectChoiceCommand {
     /// Creates a new `SelectChoiceCommand`.
-    pub(crate) fn new(choice_index: usize) -> Self {
-        SelectChoiceCommand(choice_index)
+    pub(crate) fn new(story: InkStoryId, choice_index: usize) -> Self {
+        SelectChoiceCommand {
+            story,
+            choice_index,
+        }
     }
 }
 
 impl Command for SelectChoiceCommand {
     fn apply(self, world: &mut World) {
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
-            error!(
-                "Failed to load state: Story resource not found. Did you forget to insert the InkProject resource?",
-            );
-            return;
-        };
-        let choices = story.get_current_choices();
-        if self.0 >= choices.len() {
-            error!(
-                "Invalid choice index: {}, only {} choices available",
-                self.0,
-                choices.len()
-            );
-            return;
-        }
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            let Some(story) = stories.get_mut(self.story) else {
+                error!(
+                    "Failed to select choice: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return effects;
+            };
+            let choices = story.get_current_choices();
+            if self.choice_index >= choices.len() {
+                error!(
+                    "Invalid choice index: {}, only {} choices available",
+                    self.choice_index,
+                    choices.len()
+                );
+                return effects;
+            }
 
-        match story.choose_choice_index(self.0) {
-            Ok(_) => {
-                let mut commands = world.commands();
-                commands.ink_continue_sequence();
-                world.flush();
+            match story.choose_choice_index(self.choice_index) {
+                Ok(_) => continue_story(stories, self.story, &mut effects),
+                Err(err) => error!("Failed to select choice: {}", err),
             }
-            Err(err) => error!("Failed to select choice: {}", err),
-        }
+            effects
+        });
     }
 }
 
@@ -49,9 +59,10 @@ pub trait SelectChoiceCommandsExt {
     fn ink_select_choice(&mut self, choice_index: usize) -> &mut Self;
 }
 
-impl<'w, 's> SelectChoiceCommandsExt for Commands<'w, 's> {
+impl<T: InkCommandQueue> SelectChoiceCommandsExt for T {
     fn ink_select_choice(&mut self, choice_index: usize) -> &mut Self {
-        self.queue(SelectChoiceCommand::new(choice_index));
+        let story = self.ink_story_id();
+        self.queue_ink_command(SelectChoiceCommand::new(story, choice_index));
         self
     }
 }
@@ -1,17 +1,23 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
 
-use crate::{events::InkStateChanged, ink::InkValue};
+use crate::{
+    commands::{InkCommandQueue, refresh_tracked_variable},
+    ink::InkValue,
+    resources::InkStoryId,
+    runtime::{InkEffects, run_ink},
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct SetVariableCommand {
+    pub story: InkStoryId,
     pub name: String,
     pub value: InkValue,
 }
 
 impl SetVariableCommand {
-    pub fn new(name: String, value: impl Into<InkValue>) -> Self {
+    pub fn new(story: InkStoryId, name: String, value: impl Into<InkValue>) -> Self {
         Self {
+            story,
             name,
             value: value.into(),
         }
@@ -20,21 +26,40 @@ impl SetVariableCommand {
 
 impl Command for SetVariableCommand {
     fn apply(self, world: &mut World) {
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            let Some(lists) = stories.list_definitions(self.story) else {
+                error!(
+                    "Failed to set variable: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return effects;
+            };
+            let error = match self.value.to_story_value_type(lists) {
+                Ok(value) => {
+                    let Some(story) = stories.get_mut(self.story) else {
+                        return effects;
+                    };
+                    match story.set_variable(&self.name, &value) {
+                        Ok(_) => {
+                            effects.update_state(stories, self.story);
+                            return effects;
+                        }
+                        Err(err) => err.to_string(),
+                    }
+                }
+                Err(err) => err.to_string(),
+            };
             error!(
-                "Failed to load state: Story resource not found. Did you forget to insert the InkProject resource?",
+                "Failed to set variable '{}' to value '{:?}': {}",
+                self.name, &self.value, error
             );
-            return;
-        };
-        match story.set_variable(&self.name, &(&self.value).into()) {
-            Ok(_) => {
-                world.trigger(InkStateChanged);
+            // a tracked variable goes back to the value the story kept
+            if stories.tracked(self.story).contains(&self.name) {
+                refresh_tracked_variable(stories, self.story, self.name, &mut effects);
             }
-            Err(err) => error!(
-                "Failed to set variable '{}' to value '{:?}': {}",
-                self.name, &self.value, err
-            ),
-        };
+            effects
+        });
     }
 }
 
@@ -42,9 +67,10 @@ pub trait SetVariableCommandsExt {
     fn ink_set_variable(&mut self, name: String, value: impl Into<InkValue>) -> &mut Self;
 }
 
-impl<'w, 's> SetVariableCommandsExt for Commands<'w, 's> {
+impl<T: InkCommandQueue> SetVariableCommandsExt for T {
     fn ink_set_variable(&mut self, name: String, value: impl Into<InkValue>) -> &mut Self {
-        self.queue(SetVariableCommand::new(name, value));
+        let story = self.ink_story_id();
+        self.queue_ink_command(SetVariableCommand::new(story, name, value));
         self
     }
 }
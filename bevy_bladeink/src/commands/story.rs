@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::resources::InkStoryId;
+
+/// Anything ink commands can be queued on. Implemented for `Commands`, which
+/// targets the default story, and for [`InkStoryCommands`], which targets the
+/// story it was created for.
+pub trait InkCommandQueue {
+    /// The story queued commands will be applied to.
+    fn ink_story_id(&self) -> InkStoryId;
+
+    /// Queues a command to be applied to the world.
+    fn queue_ink_command(&mut self, command: impl Command);
+}
+
+impl InkCommandQueue for Commands<'_, '_> {
+    fn ink_story_id(&self) -> InkStoryId {
+        InkStoryId::DEFAULT
+    }
+
+    fn queue_ink_command(&mut self, command: impl Command) {
+        self.queue(command);
+    }
+}
+
+/// A `Commands` wrapper whose ink commands target a specific story.
+pub struct InkStoryCommands<'a, 'w, 's> {
+    commands: &'a mut Commands<'w, 's>,
+    story: InkStoryId,
+}
+
+impl InkCommandQueue for InkStoryCommands<'_, '_, '_> {
+    fn ink_story_id(&self) -> InkStoryId {
+        self.story
+    }
+
+    fn queue_ink_command(&mut self, command: impl Command) {
+        self.commands.queue(command);
+    }
+}
+
+/// Helper trait for targeting a story other than the default one.
+pub trait InkStoryCommandsExt<'w, 's> {
+    /// Returns a wrapper whose ink commands are applied to the given story.
+    ///
+    /// ```rust,ignore
+    /// commands.ink_story(TUTORIAL).ink_begin_sequence("intro");
+    /// ```
+    fn ink_story(&mut self, story: InkStoryId) -> InkStoryCommands<'_, 'w, 's>;
+}
+
+impl<'w, 's> InkStoryCommandsExt<'w, 's> for Commands<'w, 's> {
+    fn ink_story(&mut self, story: InkStoryId) -> InkStoryCommands<'_, 'w, 's> {
+        InkStoryCommands {
+            commands: self,
+            story,
+        }
+    }
+}
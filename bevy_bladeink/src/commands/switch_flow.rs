@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use crate::{
+    commands::InkCommandQueue,
+    resources::InkStoryId,
+    runtime::{InkEffects, run_ink},
+};
+
+/// Represents a command to switch the flow an ink story is running.
+pub(crate) struct SwitchFlowCommand {
+    story: InkStoryId,
+    flow: Option<String>,
+}
+
+impl SwitchFlowCommand {
+    /// Creates a new `SwitchFlowCommand`, `None` switches to the default flow.
+    pub(crate) fn new(story: InkStoryId, flow: Option<String>) -> Self {
+        SwitchFlowCommand { story, flow }
+    }
+}
+
+impl Command for SwitchFlowCommand {
+    fn apply(self, world: &mut World) {
+        run_ink(world, move |stories| {
+            let Some((story, flows)) = stories.get_with_flows_mut(self.story) else {
+                error!(
+                    "Failed to switch flow: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return InkEffects::default();
+            };
+            match &self.flow {
+                Some(flow) => match story.switch_flow(flow) {
+                    Ok(_) => flows.switch(Some(flow)),
+                    Err(err) => warn!("Failed to switch to flow '{flow}': {err}"),
+                },
+                None => {
+                    story.switch_to_default_flow();
+                    flows.switch(None);
+                }
+            }
+            InkEffects::default()
+        });
+    }
+}
+
+/// Helper trait for adding `SwitchFlowCommand` to `Commands`.
+pub trait SwitchFlowCommandsExt {
+    /// Switches to the named flow, creating it if it doesn't exist yet. The
+    /// flow being switched away from keeps its position.
+    fn ink_switch_flow(&mut self, flow: impl Into<String>) -> &mut Self;
+
+    /// Switches back to the default flow, keeping the current flow around for
+    /// later.
+    fn ink_switch_to_default_flow(&mut self) -> &mut Self;
+}
+
+impl<T: InkCommandQueue> SwitchFlowCommandsExt for T {
+    fn ink_switch_flow(&mut self, flow: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(SwitchFlowCommand::new(story, Some(flow.into())));
+        self
+    }
+
+    fn ink_switch_to_default_flow(&mut self) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(SwitchFlowCommand::new(story, None));
+        self
+    }
+}
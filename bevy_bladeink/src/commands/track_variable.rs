@@ -1,25 +1,39 @@
+use std::{cell::RefCell, rc::Rc};
+
 use bevy::prelude::*;
 use bevy_crossbeam_event::CrossbeamEventSender;
-use bladeink::{story::Story, value_type::ValueType};
+use bladeink::{
+    story::variable_observer::VariableObserver, story_error::StoryError, value_type::ValueType,
+};
 
 use crate::{
-    ink::{InkBindingDefinition, InkBindingError, InkBindingFn, InkValue},
-    resources::InkVariables,
+    assets::StoryJson,
+    commands::InkCommandQueue,
+    events::InkVariableChanged,
+    ink::InkValue,
+    resources::{InkStories, InkStory, InkStoryId, InkVariables},
+    runtime::{InkEffects, collect_binding_event, run_ink},
+    systems::ink_story,
 };
 
 #[derive(Debug, Clone)]
 pub(crate) struct TrackVariableCommand {
+    pub story: InkStoryId,
     pub name: String,
 }
 
 impl TrackVariableCommand {
-    pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+    pub fn new(story: InkStoryId, name: impl Into<String>) -> Self {
+        Self {
+            story,
+            name: name.into(),
+        }
     }
 }
 
 #[derive(Event, Clone)]
 pub(crate) struct VariableUpdated {
+    pub story: InkStoryId,
     pub name: String,
     pub value: InkValue,
 }
@@ -27,73 +41,320 @@ pub(crate) struct VariableUpdated {
 pub(crate) fn on_variable_updated(
     variable: On<VariableUpdated>,
     mut ink_variables: ResMut<InkVariables>,
+    mut commands: Commands,
 ) {
-    ink_variables
-        .tracked_variables
-        .insert(variable.name.clone(), variable.value.clone());
+    let old = ink_variables
+        .story(variable.story)
+        .get(&variable.name)
+        .cloned();
+    ink_variables.update_from_story(
+        variable.story,
+        variable.name.clone(),
+        variable.value.clone(),
+    );
+    if old.as_ref() != Some(&variable.value) {
+        commands.trigger(InkVariableChanged {
+            story: variable.story,
+            name: variable.name.clone(),
+            old,
+            new: variable.value.clone(),
+        });
+    }
 }
 
-impl InkBindingDefinition for VariableUpdated {
-    type Event = VariableUpdated;
+/// Forwards changes of observed variables in a single story to the
+/// `VariableUpdated` channel.
+struct VariableTracker {
+    story: InkStoryId,
+    sender: CrossbeamEventSender<VariableUpdated>,
+}
 
-    fn try_parse_event(args: &[ValueType]) -> Result<Self::Event, InkBindingError> {
-        match args {
-            [ValueType::String(name), value] => Ok(VariableUpdated {
-                name: name.string.clone(),
-                value: value.clone().into(),
-            }),
-            _ => Err(InkBindingError::InvalidArguments),
+impl VariableTracker {
+    fn to_observer(
+        story: InkStoryId,
+        sender: CrossbeamEventSender<VariableUpdated>,
+    ) -> Rc<RefCell<dyn VariableObserver>> {
+        Rc::new(RefCell::new(Self { story, sender }))
+    }
+}
+
+impl VariableObserver for VariableTracker {
+    fn changed(&mut self, name: &str, value: &ValueType) {
+        let value = match InkValue::try_from(value.clone()) {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Failed to read variable '{name}': {err}");
+                return;
+            }
+        };
+        let variable = VariableUpdated {
+            story: self.story,
+            name: name.to_string(),
+            value,
+        };
+        // changes made while continuing the story are reported with the line
+        // they are made on, and dropped with it if it is rolled back
+        if let Err(variable) = collect_binding_event(variable) {
+            self.sender.send(variable);
         }
     }
 }
 
+/// Forwards the changes of the variable to [`InkVariables`], returning its
+/// current value.
+pub(crate) fn observe_variable(
+    stories: &mut InkStories,
+    id: InkStoryId,
+    name: &str,
+    sender: CrossbeamEventSender<VariableUpdated>,
+) -> Result<Option<InkValue>, StoryError> {
+    let observer = stories.variable_observer(id, || VariableTracker::to_observer(id, sender));
+    let Some(story) = stories.get_mut(id) else {
+        return Ok(None);
+    };
+    story.observe_variable(name, observer)?;
+    let value = story
+        .get_variable(name)
+        .map(InkValue::try_from)
+        .transpose()?;
+    stories.track(id, name.to_string());
+    Ok(value)
+}
+
+/// Reports the current values of the tracked variables of a story, after its
+/// state was replaced without the variable observers being notified.
+pub(crate) fn refresh_tracked_variables(
+    stories: &InkStories,
+    id: InkStoryId,
+    effects: &mut InkEffects,
+) {
+    for name in stories.tracked(id) {
+        refresh_tracked_variable(stories, id, name, effects);
+    }
+}
+
+/// Reports the current value of a tracked variable of a story, so that
+/// [`InkVariables`] drops a value the story didn't accept.
+pub(crate) fn refresh_tracked_variable(
+    stories: &InkStories,
+    id: InkStoryId,
+    name: String,
+    effects: &mut InkEffects,
+) {
+    let Some(story) = stories.get(id) else {
+        return;
+    };
+    match story.get_variable(&name).map(InkValue::try_from) {
+        Some(Ok(value)) => effects.trigger(VariableUpdated {
+            story: id,
+            name,
+            value,
+        }),
+        Some(Err(err)) => error!("Failed to read variable '{name}': {err}"),
+        None => {}
+    }
+}
+
+/// Tracks the variables of a story which aren't tracked yet.
+fn track_variables(world: &mut World, id: InkStoryId, names: Vec<String>) {
+    let Some(channel) = world.get_resource::<CrossbeamEventSender<VariableUpdated>>() else {
+        error!(
+            "Failed to track variable: CrossbeamEventSender resource not found. Did you forget to initialize the InkPlugin?"
+        );
+        return;
+    };
+    let channel = channel.clone();
+
+    run_ink(world, move |stories| {
+        let mut effects = InkEffects::default();
+        if !stories.contains(id) {
+            error!(
+                "Failed to track variable: Story {id:?} not found. Did you forget to insert the InkProject resource?"
+            );
+            return effects;
+        }
+
+        let tracked = stories.tracked(id);
+        let mut values = Vec::new();
+        for name in names {
+            if tracked.contains(&name) {
+                continue;
+            }
+            match observe_variable(stories, id, &name, channel.clone()) {
+                Ok(Some(value)) => values.push((name, value)),
+                Ok(None) => {}
+                Err(err) => error!("Failed to track variable: {}", err),
+            }
+        }
+        effects.push(move |world| {
+            let Some(mut ink_variables) = world.get_resource_mut::<InkVariables>() else {
+                error!("Failed to track variable: InkVariables resource not found.");
+                return;
+            };
+            for (name, value) in values {
+                ink_variables.update_from_story(id, name, value);
+            }
+        });
+        effects
+    });
+}
+
 impl Command for TrackVariableCommand {
     fn apply(self, world: &mut World) {
-        let Some(channel) = world.get_resource::<CrossbeamEventSender<VariableUpdated>>() else {
+        track_variables(world, self.story, vec![self.name]);
+    }
+}
+
+/// Tracks every global variable of a story, or those whose name matches a
+/// pattern.
+#[derive(Debug, Clone)]
+pub(crate) struct TrackVariablesCommand {
+    pub story: InkStoryId,
+    pub pattern: Option<String>,
+}
+
+impl Command for TrackVariablesCommand {
+    fn apply(self, world: &mut World) {
+        let globals = ink_story(world, self.story)
+            .and_then(InkStory::handle)
+            .and_then(|handle| world.get_resource::<Assets<StoryJson>>()?.get(handle))
+            .map(|story_json| story_json.metadata().globals.clone());
+        let Some(globals) = globals else {
             error!(
-                "Failed to load state: CrossbeamEventSender resource not found. Did you forget to initialize the InkPlugin?"
+                "Failed to track variables: Story {:?} not loaded.",
+                self.story
             );
             return;
         };
-        let channel = channel.clone();
+        let names = globals
+            .into_iter()
+            .filter(|name| {
+                self.pattern
+                    .as_deref()
+                    .is_none_or(|pattern| matches_pattern(pattern, name))
+            })
+            .collect();
+        track_variables(world, self.story, names);
+    }
+}
 
-        let Some(mut story) = world.get_non_send_resource_mut::<Story>() else {
-            error!(
-                "Failed to load state: Story resource not found. Did you forget to insert the InkProject resource?",
-            );
-            return;
+/// Whether the name matches a pattern in which `*` stands for any number of
+/// characters, e.g. `quest_*`.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(prefix) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = name.strip_prefix(prefix) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((suffix, middle)) = parts.split_last() else {
+        // no `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(index) = rest.find(part) else {
+            return false;
         };
+        rest = &rest[index + part.len()..];
+    }
+    rest.ends_with(suffix)
+}
 
-        match story.observe_variable(
-            &self.name,
-            InkBindingFn::<VariableUpdated>::to_observer(channel.clone()),
-        ) {
-            Ok(_) => {
-                let current_value = story.get_variable(&self.name);
-                let Some(mut ink_variables) = world.get_resource_mut::<InkVariables>() else {
-                    error!("Failed to load state: InkVariables resource not found.");
-                    return;
-                };
-                if let Some(current_value) = current_value {
-                    ink_variables
-                        .tracked_variables
-                        .insert(self.name, current_value.into());
+/// Stops tracking a variable, removing it from [`InkVariables`].
+#[derive(Debug, Clone)]
+pub(crate) struct UntrackVariableCommand {
+    pub story: InkStoryId,
+    pub name: String,
+}
+
+impl Command for UntrackVariableCommand {
+    fn apply(self, world: &mut World) {
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            match stories.untrack(self.story, &self.name) {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!("Failed to untrack variable '{}': not tracked", self.name);
+                    return effects;
                 }
+                Err(err) => error!("Failed to untrack variable '{}': {err}", self.name),
             }
-            Err(err) => {
-                error!("Failed to track variable: {}", err);
-            }
-        }
+            effects.push(move |world| {
+                if let Some(mut ink_variables) = world.get_resource_mut::<InkVariables>() {
+                    ink_variables.forget(self.story, &self.name);
+                }
+            });
+            effects
+        });
     }
 }
 
 pub trait TrackVariableCommandsExt {
     fn ink_track_variable(&mut self, name: impl Into<String>) -> &mut Self;
+
+    /// Tracks every global variable of the story.
+    fn ink_track_all_variables(&mut self) -> &mut Self;
+
+    /// Tracks the global variables whose name matches the pattern, in which
+    /// `*` stands for any number of characters, e.g. `quest_*`.
+    fn ink_track_variables_matching(&mut self, pattern: impl Into<String>) -> &mut Self;
+
+    /// Stops tracking the variable, removing it from [`InkVariables`].
+    fn ink_untrack_variable(&mut self, name: impl Into<String>) -> &mut Self;
 }
 
-impl<'w, 's> TrackVariableCommandsExt for Commands<'w, 's> {
+impl<T: InkCommandQueue> TrackVariableCommandsExt for T {
     fn ink_track_variable(&mut self, name: impl Into<String>) -> &mut Self {
-        self.queue(TrackVariableCommand::new(name));
+        let story = self.ink_story_id();
+        self.queue_ink_command(TrackVariableCommand::new(story, name));
+        self
+    }
+
+    fn ink_track_all_variables(&mut self) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(TrackVariablesCommand {
+            story,
+            pattern: None,
+        });
+        self
+    }
+
+    fn ink_track_variables_matching(&mut self, pattern: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(TrackVariablesCommand {
+            story,
+            pattern: Some(pattern.into()),
+        });
+        self
+    }
+
+    fn ink_untrack_variable(&mut self, name: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(UntrackVariableCommand {
+            story,
+            name: name.into(),
+        });
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("quest_*", "quest_started"));
+        assert!(matches_pattern("quest_*", "quest_"));
+        assert!(!matches_pattern("quest_*", "side_quest_started"));
+        assert!(matches_pattern("*_count", "color_change_count"));
+        assert!(matches_pattern("*change*", "color_change_count"));
+        assert!(matches_pattern("a*b*a", "aba"));
+        assert!(!matches_pattern("a*a", "a"));
+        assert!(matches_pattern("forceful", "forceful"));
+        assert!(!matches_pattern("forceful", "forcefully"));
+        assert!(matches_pattern("*", "anything"));
+    }
+}
@@ -1,15 +1,42 @@
 use bevy::prelude::*;
 
-/// Creates a new `InkPath` instance.
-#[derive(Component)]
+use crate::resources::InkStoryId;
+
+/// The sequence an entity starts when it is spoken to, through
+/// [`InkPathCommandsExt::ink_begin`](crate::commands::InkPathCommandsExt::ink_begin).
+#[derive(Component, Debug, Clone)]
 pub struct InkPath {
-    #[expect(dead_code, reason = "todo")]
+    pub(crate) story: InkStoryId,
+    pub(crate) flow: Option<String>,
     pub(crate) path: String,
 }
 
 impl InkPath {
-    /// Creates a new `InkPath` instance.
+    /// Creates a new `InkPath` instance, starting the given path in the
+    /// default story.
     pub fn new(path: impl Into<String>) -> Self {
-        Self { path: path.into() }
+        Self {
+            story: InkStoryId::DEFAULT,
+            flow: None,
+            path: path.into(),
+        }
+    }
+
+    /// Starts the path in the given story instead of the default one.
+    pub fn in_story(mut self, story: InkStoryId) -> Self {
+        self.story = story;
+        self
+    }
+
+    /// Starts the path in the named flow, so the entity keeps its own position
+    /// in the story.
+    pub fn in_flow(mut self, flow: impl Into<String>) -> Self {
+        self.flow = Some(flow.into());
+        self
+    }
+
+    /// The path the sequence starts at.
+    pub fn path(&self) -> &str {
+        &self.path
     }
 }
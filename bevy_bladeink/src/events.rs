@@ -1,38 +1,174 @@
 use bevy::prelude::*;
 
-use crate::ink::{ChoiceItem, InkState};
+use crate::{
+    ink::{ChoiceItem, InkResumeToken, InkState, InkStateError, InkValue},
+    resources::InkStoryId,
+};
 
+/// Emitted once a story has been parsed and is ready to receive commands.
 #[derive(Event, Clone, Debug)]
-pub struct StoryReady;
+pub struct StoryReady {
+    pub story: InkStoryId,
+}
 
-#[derive(Event, Debug)]
-pub(crate) struct InkStateChanged;
+/// Emitted instead of [`StoryReady`] when the asset of a loaded story changes,
+/// once the story has been rebuilt. The state of the previous story is
+/// restored if it is compatible with the new one, otherwise the story starts
+/// over.
+#[derive(Event, Clone, Debug)]
+pub struct StoryReloaded {
+    pub story: InkStoryId,
+    /// Whether the state of the previous story was restored.
+    pub restored: bool,
+}
+
+/// The step of loading a story which failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InkLoadStage {
+    /// The asset could not be read.
+    AssetIo,
+    /// The `.ink` source of the story does not compile.
+    Compile,
+    /// The asset is not a valid story JSON file.
+    JsonParse,
+    /// The runtime could not create the story from its JSON.
+    StoryConstruction,
+    /// The saved state of the story could not be applied to it.
+    StateRestore,
+    /// The story was refused by strict binding validation, see
+    /// [`InkBindingReport`].
+    Bindings,
+}
 
-/// Published when the state of the ink story changes. This is a bit of a hack
-/// for now, better options for persistence will be implemented in the future.
+/// Emitted instead of [`StoryReady`] when a story fails to load. Failures
+/// while reloading a running story are reported too, while the previous
+/// version of the story keeps running.
 #[derive(Event, Clone, Debug)]
-pub struct InkStateUpdate(pub InkState);
+pub struct StoryLoadFailed {
+    pub story: InkStoryId,
+    /// The asset path of the story.
+    pub path: String,
+    pub stage: InkLoadStage,
+    pub error: String,
+}
+
+/// Emitted when a story is loaded or reloaded, before [`StoryReady`] or
+/// [`StoryReloaded`], comparing the `EXTERNAL` functions the story calls with
+/// the bindings registered for it. With
+/// [`InkBindingValidation::strict`](crate::resources::InkBindingValidation),
+/// a story with errors is not started.
+#[derive(Event, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InkBindingReport {
+    pub story: InkStoryId,
+    /// Functions the story calls which have neither a binding nor an ink
+    /// fallback function.
+    pub missing: Vec<String>,
+    /// Bindings of functions the story never calls.
+    pub unused: Vec<String>,
+    /// Bindings expecting a different number of arguments than the story
+    /// calls them with.
+    pub arity_mismatches: Vec<InkArityMismatch>,
+}
+
+impl InkBindingReport {
+    /// Whether the story calls functions it can't run. Unused bindings are not
+    /// errors.
+    pub fn has_errors(&self) -> bool {
+        !self.missing.is_empty() || !self.arity_mismatches.is_empty()
+    }
+}
+
+/// A binding called with a different number of arguments than it expects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InkArityMismatch {
+    pub name: String,
+    /// The number of arguments the binding expects.
+    pub expected: usize,
+    /// The number of arguments the story calls it with.
+    pub called_with: usize,
+}
+
+/// Published when the state of the ink story changes. The state is only
+/// serialized while this event is observed. See
+/// [`InkAutosave`](crate::save::InkAutosave) to only save the story at chosen
+/// points instead.
+#[derive(Event, Clone, Debug)]
+pub struct InkStateUpdate {
+    pub story: InkStoryId,
+    pub state: InkState,
+}
+
+/// Emitted when a state passed to `ink_load_state` can't be loaded, e.g. as it
+/// was saved from another version of the story. The story carries on from its
+/// current state.
+#[derive(Event, Clone, Debug)]
+pub struct StateLoadFailed {
+    pub story: InkStoryId,
+    pub error: InkStateError,
+}
+
+/// Emitted when a story changes one of its tracked variables. Changes made
+/// through [`InkVariables`](crate::resources::InkVariables) are not reported
+/// again when the story applies them.
+#[derive(Event, Clone, Debug)]
+pub struct InkVariableChanged {
+    pub story: InkStoryId,
+    pub name: String,
+    /// The previous value of the variable, if it was known.
+    pub old: Option<InkValue>,
+    pub new: InkValue,
+}
 
 /// After a successful `BeginSequence` command is sent, this event is emitted.
 #[derive(Event, Clone, Debug)]
-pub struct SequenceBegin(pub String);
+pub struct SequenceBegin {
+    pub story: InkStoryId,
+    /// The flow the sequence was started in, `None` for the default flow.
+    pub flow: Option<String>,
+    pub path: String,
+}
 
 /// After a successful `ContinueSequenceCommand` is issued, if a new line of
 /// content is produced, this event will be emitted containing the new line.
 #[derive(Event, Clone, Debug)]
-pub struct SequenceEnd;
+pub struct SequenceEnd {
+    pub story: InkStoryId,
+    /// The flow which ended, `None` for the default flow.
+    pub flow: Option<String>,
+}
+
+/// Emitted when the story calls a blocking binding, and again whenever a
+/// `ContinueSequenceCommand` is refused because the story is still waiting on
+/// it. Resume the story with the token, through `ink_resume`.
+#[derive(Event, Clone, Debug)]
+pub struct SequenceSuspended {
+    pub story: InkStoryId,
+    /// The flow which was suspended, `None` for the default flow.
+    pub flow: Option<String>,
+    /// The blocking function the story is waiting on.
+    pub function: String,
+    pub token: InkResumeToken,
+}
 
 /// After a successful `ContinueSequenceCommand` is issued, if a new line of
 /// content is produced, this event will be emitted containing the new line.
 #[derive(Event, Clone, Debug)]
 pub struct DeliverLine {
+    pub story: InkStoryId,
+    /// The flow the line was produced in, `None` for the default flow.
+    pub flow: Option<String>,
     pub text: String,
     pub tags: Vec<String>,
 }
 
 impl DeliverLine {
-    pub fn new(text: String, tags: Vec<String>) -> Self {
-        Self { text, tags }
+    pub fn new(story: InkStoryId, flow: Option<String>, text: String, tags: Vec<String>) -> Self {
+        Self {
+            story,
+            flow,
+            text,
+            tags,
+        }
     }
 }
 
@@ -41,11 +177,41 @@ impl DeliverLine {
 /// containing the available choices.
 #[derive(Event, Clone, Debug)]
 pub struct DeliverChoices {
+    pub story: InkStoryId,
+    /// The flow the choices were produced in, `None` for the default flow.
+    pub flow: Option<String>,
     pub choices: Vec<ChoiceItem>,
 }
 
 impl DeliverChoices {
-    pub fn new(choices: Vec<ChoiceItem>) -> Self {
-        Self { choices }
+    pub fn new(story: InkStoryId, flow: Option<String>, choices: Vec<ChoiceItem>) -> Self {
+        Self {
+            story,
+            flow,
+            choices,
+        }
+    }
+}
+
+/// A copy of a sequence event, triggered on the entity whose
+/// [`InkPath`](crate::components::InkPath) started the sequence. Observe it on
+/// the entity to only receive the dialogue it is taking part in:
+///
+/// ```rust,ignore
+/// commands
+///     .entity(npc)
+///     .observe(|line: On<InkEntityEvent<DeliverLine>>| info!("{}", line.text));
+/// ```
+#[derive(EntityEvent, Clone, Debug)]
+pub struct InkEntityEvent<E: Event + Clone> {
+    pub entity: Entity,
+    pub event: E,
+}
+
+impl<E: Event + Clone> std::ops::Deref for InkEntityEvent<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
     }
 }
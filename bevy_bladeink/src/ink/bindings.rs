@@ -1,13 +1,19 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, marker::PhantomData, rc::Rc, sync::Arc};
 
 use bevy::{platform::collections::HashMap, prelude::*};
-use bevy_crossbeam_event::{CrossbeamEventApp, CrossbeamEventSender};
-use bladeink::{
-    story::{external_functions::ExternalFunction, variable_observer::VariableObserver},
-    value_type::ValueType,
-};
+use bevy_crossbeam_event::CrossbeamEventSender;
+use bladeink::{story::external_functions::ExternalFunction, value_type::ValueType};
 use thiserror::Error;
 
+use crate::{
+    ink::{
+        InkBlockingFn, InkSystemBindingFn, InkSystemOutput, InkSystemRunner, InkValue, call_once,
+        create_system_runner,
+    },
+    resources::InkStoryId,
+    runtime::{InkDeferredEffects, InkEffects, ink_running, run_ink, trigger_binding_event},
+};
+
 /// Error type for ink bindings.
 /// This error type is used to handle the possible failures that can occur
 /// attempting to bind an ink function to a Bevy app.
@@ -31,6 +37,20 @@ pub trait InkBindingDefinition: Clone + 'static {
     /// Event type for the binding.
     type Event: Event + Clone;
 
+    /// Whether the binding can be called while the story looks ahead for
+    /// glue, rather than stopping the lookahead. Only mark bindings without
+    /// side effects which depend on the line being delivered.
+    const LOOKAHEAD_SAFE: bool = false;
+
+    /// Whether the story may use the ink fallback function of the same name
+    /// when the binding is not bound. Fallbacks can only be enabled for the
+    /// whole story, so any binding asking for them enables them.
+    const ALLOW_FALLBACK: bool = false;
+
+    /// The number of arguments the binding expects, checked against the calls
+    /// the story makes when it is loaded. `None` skips the check.
+    const ARITY: Option<usize> = None;
+
     /// Parses the event from the given arguments.
     fn try_parse_event(args: &[ValueType]) -> Result<Self::Event, InkBindingError>;
 
@@ -44,6 +64,10 @@ pub trait InkBindingDefinition: Clone + 'static {
 ///
 /// This trait provides a way to bind an ink function to a Bevy app.
 /// The ink function can be called from within the ink script and will trigger the associated event.
+///
+/// Bindings can also be changed while the stories are running, from an
+/// exclusive system through `World`, or with
+/// [`InkBindingCommandsExt`](crate::commands::InkBindingCommandsExt).
 pub trait AddInkBindingApp {
     /// Bind an ink command to the application.
     fn bind_ink_function<T: InkBindingDefinition + 'static>(
@@ -52,6 +76,108 @@ pub trait AddInkBindingApp {
     ) -> &mut Self
     where
         for<'a> <T::Event as Event>::Trigger<'a>: Default;
+
+    /// Bind an ink command for a single story. Takes precedence over a
+    /// binding with the same name registered with
+    /// [`bind_ink_function`](AddInkBindingApp::bind_ink_function).
+    fn bind_ink_function_for<T: InkBindingDefinition + 'static>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default;
+
+    /// Bind an ink function which suspends the story, for things like
+    /// cutscenes which must finish before the story carries on. The event is
+    /// triggered as usual, and the story emits a
+    /// [`SequenceSuspended`](crate::events::SequenceSuspended) event holding
+    /// the token to resume it with, through `ink_resume`.
+    fn bind_ink_blocking_function<T: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default;
+
+    /// Bind an ink function which suspends the story, for a single story.
+    fn bind_ink_blocking_function_for<T: InkBindingDefinition + 'static>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default;
+
+    /// Bind an ink function to a system. The system receives the parsed
+    /// arguments through `In`, and runs synchronously while the story
+    /// executes, so it can read the world and return a value to ink.
+    ///
+    /// ```rust,ignore
+    /// #[derive(Event, Clone, InkBinding)]
+    /// struct HasItem(String);
+    ///
+    /// fn has_item(In(HasItem(item)): In<HasItem>, inventory: Res<Inventory>) -> bool {
+    ///     inventory.contains(&item)
+    /// }
+    ///
+    /// app.bind_ink_system("has_item", has_item);
+    /// ```
+    fn bind_ink_system<E, Out, M>(
+        &mut self,
+        name: impl AsRef<str>,
+        system: impl IntoSystem<In<E>, Out, M> + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput;
+
+    /// Bind an ink function to a system for a single story. Takes precedence
+    /// over a binding with the same name registered for every story.
+    fn bind_ink_system_for<E, Out, M>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+        system: impl IntoSystem<In<E>, Out, M> + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput;
+
+    /// Bind an ink function to a closure computing its return value, for
+    /// small helpers which need neither an event nor access to the world.
+    /// The closure is expected to be pure, so the binding is lookahead-safe.
+    ///
+    /// ```rust,ignore
+    /// app.bind_ink_fn("clamp", |args: &[InkValue]| match args {
+    ///     [InkValue::Int(value), InkValue::Int(min), InkValue::Int(max)] => {
+    ///         Some(InkValue::Int(*value.clamp(min, max)))
+    ///     }
+    ///     _ => None,
+    /// });
+    /// ```
+    fn bind_ink_fn(
+        &mut self,
+        name: impl AsRef<str>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Bind an ink function to a closure for a single story. Takes precedence
+    /// over a binding with the same name registered for every story.
+    fn bind_ink_fn_for(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Removes the binding of an ink function registered for every story.
+    /// Stories with a binding of their own for the function keep it.
+    fn unbind_ink_function(&mut self, name: impl AsRef<str>) -> &mut Self;
+
+    /// Removes the binding of an ink function registered for a single story.
+    /// The binding registered for every story applies to it again, if any.
+    fn unbind_ink_function_for(&mut self, story: InkStoryId, name: impl AsRef<str>) -> &mut Self;
 }
 
 impl AddInkBindingApp for App {
@@ -62,62 +188,446 @@ impl AddInkBindingApp for App {
     where
         for<'a> <T::Event as Event>::Trigger<'a>: Default,
     {
-        let world = self.world_mut();
-        if world
-            .get_resource::<CrossbeamEventSender<T::Event>>()
-            .is_none()
+        self.world_mut().bind_ink_function::<T>(name);
+        self
+    }
+
+    fn bind_ink_function_for<T: InkBindingDefinition + 'static>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        self.world_mut().bind_ink_function_for::<T>(story, name);
+        self
+    }
+
+    fn bind_ink_blocking_function<T: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        self.world_mut().bind_ink_blocking_function::<T>(name);
+        self
+    }
+
+    fn bind_ink_blocking_function_for<T: InkBindingDefinition + 'static>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        self.world_mut()
+            .bind_ink_blocking_function_for::<T>(story, name);
+        self
+    }
+
+    fn bind_ink_system<E, Out, M>(
+        &mut self,
+        name: impl AsRef<str>,
+        system: impl IntoSystem<In<E>, Out, M> + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput,
+    {
+        self.world_mut().bind_ink_system(name, system);
+        self
+    }
+
+    fn bind_ink_system_for<E, Out, M>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+        system: impl IntoSystem<In<E>, Out, M> + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput,
+    {
+        self.world_mut().bind_ink_system_for(story, name, system);
+        self
+    }
+
+    fn bind_ink_fn(
+        &mut self,
+        name: impl AsRef<str>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut().bind_ink_fn(name, function);
+        self
+    }
+
+    fn bind_ink_fn_for(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut().bind_ink_fn_for(story, name, function);
+        self
+    }
+
+    fn unbind_ink_function(&mut self, name: impl AsRef<str>) -> &mut Self {
+        self.world_mut().unbind_ink_function(name);
+        self
+    }
+
+    fn unbind_ink_function_for(&mut self, story: InkStoryId, name: impl AsRef<str>) -> &mut Self {
+        self.world_mut().unbind_ink_function_for(story, name);
+        self
+    }
+}
+
+impl AddInkBindingApp for World {
+    fn bind_ink_function<T: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        let binding = create_binding::<T>(self);
+        insert_binding(self, None, name.as_ref(), binding);
+        self
+    }
+
+    fn bind_ink_function_for<T: InkBindingDefinition + 'static>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        let binding = create_binding::<T>(self);
+        insert_binding(self, Some(story), name.as_ref(), binding);
+        self
+    }
+
+    fn bind_ink_blocking_function<T: InkBindingDefinition + 'static>(
+        &mut self,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        let binding = create_blocking_binding::<T>(self);
+        insert_binding(self, None, name.as_ref(), binding);
+        self
+    }
+
+    fn bind_ink_blocking_function_for<T: InkBindingDefinition + 'static>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+    ) -> &mut Self
+    where
+        for<'a> <T::Event as Event>::Trigger<'a>: Default,
+    {
+        let binding = create_blocking_binding::<T>(self);
+        insert_binding(self, Some(story), name.as_ref(), binding);
+        self
+    }
+
+    fn bind_ink_system<E, Out, M>(
+        &mut self,
+        name: impl AsRef<str>,
+        system: impl IntoSystem<In<E>, Out, M> + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput,
+    {
+        let binding = create_system_binding(self, system);
+        insert_binding(self, None, name.as_ref(), binding);
+        self
+    }
+
+    fn bind_ink_system_for<E, Out, M>(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+        system: impl IntoSystem<In<E>, Out, M> + 'static,
+    ) -> &mut Self
+    where
+        E: InkBindingDefinition<Event = E>,
+        Out: InkSystemOutput,
+    {
+        let binding = create_system_binding(self, system);
+        insert_binding(self, Some(story), name.as_ref(), binding);
+        self
+    }
+
+    fn bind_ink_fn(
+        &mut self,
+        name: impl AsRef<str>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self {
+        insert_binding(self, None, name.as_ref(), create_closure_binding(function));
+        self
+    }
+
+    fn bind_ink_fn_for(
+        &mut self,
+        story: InkStoryId,
+        name: impl AsRef<str>,
+        function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+    ) -> &mut Self {
+        insert_binding(
+            self,
+            Some(story),
+            name.as_ref(),
+            create_closure_binding(function),
+        );
+        self
+    }
+
+    fn unbind_ink_function(&mut self, name: impl AsRef<str>) -> &mut Self {
+        self.get_non_send_resource_mut::<InkBindingMap>()
+            .expect("Failed to locate ink binding definitions storage, did you forget to initialize the bevy_bladeink plugin?")
+            .remove(name.as_ref());
+        apply_binding(self, None, name.as_ref());
+        self
+    }
+
+    fn unbind_ink_function_for(&mut self, story: InkStoryId, name: impl AsRef<str>) -> &mut Self {
+        if let Some(mut binding_map) = self.get_non_send_resource_mut::<InkStoryBindingMap>()
+            && let Some(bindings) = binding_map.get_mut(&story)
         {
-            self.add_crossbeam_event::<T::Event>();
+            bindings.remove(name.as_ref());
         }
+        apply_binding(self, Some(story), name.as_ref());
+        self
+    }
+}
 
-        let world = self.world_mut();
+/// Stores the binding for every story, or only for the given one.
+fn insert_binding(world: &mut World, story: Option<InkStoryId>, name: &str, binding: InkBinding) {
+    match story {
+        None => {
+            let mut binding_map = world
+                .get_non_send_resource_mut::<InkBindingMap>()
+                .expect("Failed to locate ink binding definitions storage, did you forget to initialize the bevy_bladeink plugin?");
+            binding_map.insert(name.to_string(), binding);
+        }
+        Some(story) => {
+            let mut binding_map = world
+                .get_non_send_resource_mut::<InkStoryBindingMap>()
+                .expect("Failed to locate ink binding definitions storage, did you forget to initialize the bevy_bladeink plugin?");
+            binding_map
+                .entry(story)
+                .or_default()
+                .insert(name.to_string(), binding);
+        }
+    }
+    apply_binding(world, story, name);
+}
 
-        let channel = world
-            .get_resource::<CrossbeamEventSender<T::Event>>()
-            .expect("CrossbeamEventSender initialized above. If you see this error, it means that the bevy_bladeink plugin was not initialized correctly.")
-            .clone();
+/// Applies the current binding of the function to the loaded stories, every
+/// story or only the given one, unbinding it where it no longer has one.
+/// Stories loaded later pick their bindings up as they are created.
+fn apply_binding(world: &mut World, story: Option<InkStoryId>, name: &str) {
+    if !ink_running(world) {
+        return;
+    }
+    let global = world
+        .get_non_send_resource::<InkBindingMap>()
+        .and_then(|binding_map| binding_map.get(name))
+        .cloned();
+    // story specific bindings take precedence over global ones
+    let story_bindings: HashMap<InkStoryId, InkBinding> = world
+        .get_non_send_resource::<InkStoryBindingMap>()
+        .into_iter()
+        .flat_map(|binding_map| binding_map.iter())
+        .filter(|(id, _)| story.is_none_or(|story| story == **id))
+        .filter_map(|(id, bindings)| Some((*id, bindings.get(name)?.clone())))
+        .collect();
+    let name = name.to_string();
+    run_ink(world, move |stories| {
+        let ids: Vec<_> = stories
+            .ids()
+            .filter(|id| story.is_none_or(|story| story == *id))
+            .collect();
+        for id in ids {
+            match story_bindings.get(&id).or(global.as_ref()) {
+                Some(binding) => stories.bind(id, &name, binding),
+                None => stories.unbind(id, &name),
+            }
+        }
+        InkEffects::default()
+    });
+}
 
-        let mut binding_map = world
-            .get_non_send_resource_mut::<InkBindingMap>()
-            .expect("Failed to locate ink binding definitions storage, did you forget to initialize the bevy_bladeink plugin?");
+/// Creates the external function for a system binding, registering the system
+/// with the world.
+fn create_system_binding<E, Out, M>(
+    world: &mut World,
+    system: impl IntoSystem<In<E>, Out, M> + 'static,
+) -> InkBinding
+where
+    E: InkBindingDefinition<Event = E>,
+    Out: InkSystemOutput,
+{
+    let runner: InkSystemRunner = create_system_runner(world, system);
+
+    #[cfg(not(feature = "threaded"))]
+    {
+        world.init_resource::<crate::ink::InkSystemBindings>();
+        InkBinding::new(Arc::new(move || {
+            InkSystemBindingFn::to_binding(runner.clone())
+        }))
+        .with_options::<E>()
+    }
 
-        binding_map.insert(
-            name.as_ref().to_string(),
-            InkBindingFn::<T>::to_binding(channel.clone()),
-        );
+    #[cfg(feature = "threaded")]
+    {
+        let calls = world
+            .get_resource::<crate::resources::InkRuntime>()
+            .expect("Failed to locate the ink runtime, did you forget to initialize the bevy_bladeink plugin?")
+            .system_calls();
+        InkBinding::new(Arc::new(move || {
+            InkSystemBindingFn::to_binding(runner.clone(), calls.clone())
+        }))
+        .with_options::<E>()
+    }
+}
+
+/// Creates the external function for a binding.
+fn create_binding<T: InkBindingDefinition + 'static>(world: &World) -> InkBinding
+where
+    for<'a> <T::Event as Event>::Trigger<'a>: Default,
+{
+    let channel = binding_channel(world);
+    InkBinding::new(Arc::new(move || {
+        InkBindingFn::<T>::to_binding(channel.clone())
+    }))
+    .with_options::<T>()
+}
+
+/// Creates the external function for a binding which suspends the story.
+fn create_blocking_binding<T: InkBindingDefinition + 'static>(world: &World) -> InkBinding
+where
+    for<'a> <T::Event as Event>::Trigger<'a>: Default,
+{
+    let channel = binding_channel(world);
+    InkBinding::blocking(Arc::new(move || {
+        InkBlockingFn::<T>::to_binding(channel.clone())
+    }))
+    .with_options::<T>()
+}
+
+/// Creates the external function for a closure binding.
+pub(crate) fn create_closure_binding(
+    function: impl Fn(&[InkValue]) -> Option<InkValue> + Send + Sync + 'static,
+) -> InkBinding {
+    let function: InkClosure = Arc::new(function);
+    let mut binding = InkBinding::new(Arc::new(move || InkClosureFn::to_binding(function.clone())));
+    binding.lookahead_safe = true;
+    binding
+}
+
+/// Returns the channel the events of bindings called outside of an ink command
+/// are sent through.
+fn binding_channel(world: &World) -> CrossbeamEventSender<InkDeferredEffects> {
+    world
+        .get_resource::<CrossbeamEventSender<InkDeferredEffects>>()
+        .expect("Failed to locate the ink binding channel, did you forget to initialize the bevy_bladeink plugin?")
+        .clone()
+}
+
+/// Creates the external function for a binding. Called on whichever thread the
+/// story is created on, once for every story the binding applies to.
+pub(crate) type InkBindingFactory =
+    Arc<dyn Fn() -> Rc<RefCell<dyn ExternalFunction>> + Send + Sync>;
+
+/// A binding, along with how the story treats it.
+#[derive(Clone)]
+pub(crate) struct InkBinding {
+    factory: InkBindingFactory,
+    /// Whether calling the binding suspends the story until it is resumed.
+    pub(crate) blocking: bool,
+    /// Whether the binding can be called during lookahead.
+    pub(crate) lookahead_safe: bool,
+    /// Whether the story may fall back to the ink function of the same name.
+    pub(crate) allow_fallback: bool,
+    /// The number of arguments the binding expects, if known.
+    pub(crate) arity: Option<usize>,
+}
+
+impl InkBinding {
+    pub(crate) fn new(factory: InkBindingFactory) -> Self {
+        Self {
+            factory,
+            blocking: false,
+            lookahead_safe: false,
+            allow_fallback: false,
+            arity: None,
+        }
+    }
 
+    pub(crate) fn blocking(factory: InkBindingFactory) -> Self {
+        Self {
+            blocking: true,
+            ..Self::new(factory)
+        }
+    }
+
+    /// Reads the options of the binding from its definition.
+    pub(crate) fn with_options<T: InkBindingDefinition>(mut self) -> Self {
+        // blocking bindings always stop the lookahead, so the story suspends
+        // on the line they are called from
+        self.lookahead_safe = T::LOOKAHEAD_SAFE && !self.blocking;
+        self.allow_fallback = T::ALLOW_FALLBACK;
+        self.arity = T::ARITY;
         self
     }
+
+    /// Creates the external function to bind to a story.
+    pub(crate) fn create(&self) -> Rc<RefCell<dyn ExternalFunction>> {
+        (self.factory)()
+    }
 }
 
 /// Storage for ink bindings.
-pub(crate) type InkBindingMap = HashMap<String, Rc<RefCell<dyn ExternalFunction>>>;
+pub(crate) type InkBindingMap = HashMap<String, InkBinding>;
 
-/// Phantom ink binding that does nothing.
+/// Storage for ink bindings which only apply to a single story.
+pub(crate) type InkStoryBindingMap = HashMap<InkStoryId, InkBindingMap>;
+
+/// Ink binding triggering its event, in order with the content the story
+/// produces.
 #[derive(Clone)]
 pub(crate) struct InkBindingFn<B: InkBindingDefinition> {
-    sender: CrossbeamEventSender<B::Event>,
+    sender: CrossbeamEventSender<InkDeferredEffects>,
+    binding: PhantomData<B>,
 }
 
-impl<B: InkBindingDefinition> InkBindingFn<B> {
+impl<B: InkBindingDefinition> InkBindingFn<B>
+where
+    for<'a> <B::Event as Event>::Trigger<'a>: Default,
+{
     /// Create a new phantom ink binding.
     pub(crate) fn to_binding(
-        sender: CrossbeamEventSender<B::Event>,
+        sender: CrossbeamEventSender<InkDeferredEffects>,
     ) -> Rc<RefCell<dyn ExternalFunction>> {
-        Rc::new(RefCell::new(Self { sender }))
+        Rc::new(RefCell::new(Self {
+            sender,
+            binding: PhantomData,
+        }))
     }
 }
 
-impl<B: InkBindingDefinition> InkBindingFn<B> {
-    /// Create a new phantom ink binding.
-    pub(crate) fn to_observer(
-        sender: CrossbeamEventSender<B::Event>,
-    ) -> Rc<RefCell<dyn VariableObserver>> {
-        Rc::new(RefCell::new(Self { sender }))
-    }
-}
-
-impl<B: InkBindingDefinition> ExternalFunction for InkBindingFn<B> {
+impl<B: InkBindingDefinition> ExternalFunction for InkBindingFn<B>
+where
+    for<'a> <B::Event as Event>::Trigger<'a>: Default,
+{
     fn call(&mut self, name: &str, args: Vec<ValueType>) -> Option<ValueType> {
         let event = match B::try_parse_event(&args[..]) {
             Ok(event) => event,
@@ -127,21 +637,32 @@ impl<B: InkBindingDefinition> ExternalFunction for InkBindingFn<B> {
             }
         };
         let retval: Option<ValueType> = B::evaluate(&event).map(Into::into);
-        self.sender.send(event);
+        // outside of a command, the event arrives through its channel instead
+        trigger_binding_event(&self.sender, event);
         retval
     }
 }
 
-impl<B: InkBindingDefinition> VariableObserver for InkBindingFn<B> {
-    fn changed(&mut self, name: &str, value: &ValueType) {
-        let event = match B::try_parse_event(&[ValueType::from(name), value.clone()]) {
-            Ok(event) => event,
-            Err(err) => {
-                error!("Failed to invoke ink binding '{name}': {err:?}");
-                return;
-            }
-        };
-        self.sender.send(event);
+/// Closure computing the return value of an ink function.
+type InkClosure = Arc<dyn Fn(&[InkValue]) -> Option<InkValue> + Send + Sync>;
+
+/// Ink binding calling a closure, without triggering any event.
+struct InkClosureFn {
+    function: InkClosure,
+}
+
+impl InkClosureFn {
+    fn to_binding(function: InkClosure) -> Rc<RefCell<dyn ExternalFunction>> {
+        Rc::new(RefCell::new(Self { function }))
+    }
+}
+
+impl ExternalFunction for InkClosureFn {
+    fn call(&mut self, _name: &str, args: Vec<ValueType>) -> Option<ValueType> {
+        call_once(|| {
+            let args = InkValue::from_args(args)?;
+            (self.function)(&args)?.to_value_type()
+        })
     }
 }
 
@@ -150,8 +671,18 @@ mod tests {
     use std::f32;
 
     use super::*;
+    use bevy_crossbeam_event::CrossbeamEventApp;
     use bladeink::value_type::ValueType;
 
+    /// An app with the binding storage, without the rest of the plugin.
+    fn binding_app() -> App {
+        let mut app = App::new();
+        app.init_non_send_resource::<InkBindingMap>()
+            .init_non_send_resource::<InkStoryBindingMap>()
+            .add_crossbeam_event::<InkDeferredEffects>();
+        app
+    }
+
     // Test event with no arguments
     #[derive(Event, Clone, Debug, PartialEq)]
     struct NoArgsEvent;
@@ -460,8 +991,7 @@ mod tests {
 
     #[test]
     fn test_bind_ink_command_adds_binding() {
-        let mut app = App::new();
-        app.init_non_send_resource::<InkBindingMap>();
+        let mut app = binding_app();
         app.bind_ink_function::<NoArgsEvent>("test_command");
 
         let world = app.world();
@@ -474,8 +1004,7 @@ mod tests {
 
     #[test]
     fn test_bind_multiple_commands() {
-        let mut app = App::new();
-        app.init_non_send_resource::<InkBindingMap>();
+        let mut app = binding_app();
 
         app.bind_ink_function::<NoArgsEvent>("command1");
         app.bind_ink_function::<SingleStringEvent>("command2");
@@ -492,10 +1021,110 @@ mod tests {
         assert_eq!(binding_map.len(), 3);
     }
 
+    #[test]
+    fn test_bind_story_command_adds_story_binding() {
+        let mut app = binding_app();
+        app.bind_ink_function_for::<NoArgsEvent>(InkStoryId(1), "story_command");
+
+        let world = app.world();
+        let global_map = world
+            .get_non_send_resource::<InkBindingMap>()
+            .expect("InkBindingMap should exist");
+        let story_map = world
+            .get_non_send_resource::<InkStoryBindingMap>()
+            .expect("InkStoryBindingMap should exist");
+
+        assert!(global_map.is_empty());
+        assert!(story_map[&InkStoryId(1)].contains_key("story_command"));
+        assert!(!story_map.contains_key(&InkStoryId::DEFAULT));
+    }
+
+    // Test event which is safe to call during lookahead
+    #[derive(Event, Clone, Debug, PartialEq)]
+    struct LookaheadSafeEvent;
+
+    impl InkBindingDefinition for LookaheadSafeEvent {
+        type Event = Self;
+
+        const LOOKAHEAD_SAFE: bool = true;
+        const ALLOW_FALLBACK: bool = true;
+
+        fn try_parse_event(_args: &[ValueType]) -> Result<Self::Event, InkBindingError> {
+            Ok(LookaheadSafeEvent)
+        }
+    }
+
+    #[test]
+    fn test_binding_options_read_from_definition() {
+        let mut app = binding_app();
+        app.bind_ink_function::<NoArgsEvent>("default_options");
+        app.bind_ink_function::<LookaheadSafeEvent>("safe");
+        app.bind_ink_blocking_function::<LookaheadSafeEvent>("blocking");
+
+        let binding_map = app
+            .world()
+            .get_non_send_resource::<InkBindingMap>()
+            .expect("InkBindingMap should exist");
+
+        assert!(!binding_map["default_options"].lookahead_safe);
+        assert!(!binding_map["default_options"].allow_fallback);
+        assert!(binding_map["safe"].lookahead_safe);
+        assert!(binding_map["safe"].allow_fallback);
+        // blocking bindings always stop the lookahead
+        assert!(!binding_map["blocking"].lookahead_safe);
+        assert!(binding_map["blocking"].allow_fallback);
+    }
+
+    #[test]
+    fn test_unbind_command_removes_binding() {
+        let mut app = binding_app();
+        app.bind_ink_function::<NoArgsEvent>("command");
+        app.bind_ink_function_for::<NoArgsEvent>(InkStoryId(1), "command");
+
+        app.unbind_ink_function("command");
+        assert!(app.world().non_send_resource::<InkBindingMap>().is_empty());
+        assert!(
+            app.world().non_send_resource::<InkStoryBindingMap>()[&InkStoryId(1)]
+                .contains_key("command")
+        );
+
+        app.unbind_ink_function_for(InkStoryId(1), "command");
+        assert!(app.world().non_send_resource::<InkStoryBindingMap>()[&InkStoryId(1)].is_empty());
+    }
+
+    #[test]
+    fn test_closure_binding_returns_value() {
+        let mut app = binding_app();
+        app.bind_ink_fn("clamp", |args: &[InkValue]| match args {
+            [InkValue::Int(value), InkValue::Int(min), InkValue::Int(max)] => {
+                Some(InkValue::Int(*value.clamp(min, max)))
+            }
+            _ => None,
+        });
+
+        let binding_map = app
+            .world()
+            .get_non_send_resource::<InkBindingMap>()
+            .expect("InkBindingMap should exist");
+        let binding = &binding_map["clamp"];
+        assert!(binding.lookahead_safe);
+
+        let function = binding.create();
+        let result = function.borrow_mut().call(
+            "clamp",
+            vec![ValueType::Int(12), ValueType::Int(0), ValueType::Int(10)],
+        );
+        assert!(matches!(result, Some(ValueType::Int(10))));
+
+        let result = function
+            .borrow_mut()
+            .call("clamp", vec![ValueType::from("12")]);
+        assert!(result.is_none());
+    }
+
     #[test]
     fn test_external_function_call_with_valid_args() {
-        let mut app = App::new();
-        app.init_non_send_resource::<InkBindingMap>();
+        let mut app = binding_app();
         app.bind_ink_function::<SingleStringEvent>("test_func");
 
         let world = app.world();
@@ -506,7 +1135,7 @@ mod tests {
         let binding = binding_map
             .get("test_func")
             .expect("Binding should exist")
-            .clone();
+            .create();
 
         // Call the external function with valid arguments
         let args = vec![ValueType::from("hello")];
@@ -518,8 +1147,7 @@ mod tests {
 
     #[test]
     fn test_external_function_call_with_invalid_args() {
-        let mut app = App::new();
-        app.init_non_send_resource::<InkBindingMap>();
+        let mut app = binding_app();
         app.bind_ink_function::<SingleStringEvent>("test_func");
 
         let world = app.world();
@@ -530,7 +1158,7 @@ mod tests {
         let binding = binding_map
             .get("test_func")
             .expect("Binding should exist")
-            .clone();
+            .create();
 
         // Call with wrong argument type (should log error but not panic)
         let args = vec![ValueType::Int(42)];
@@ -542,8 +1170,7 @@ mod tests {
 
     #[test]
     fn test_binding_can_be_called_multiple_times() {
-        let mut app = App::new();
-        app.init_non_send_resource::<InkBindingMap>();
+        let mut app = binding_app();
         app.bind_ink_function::<SingleIntEvent>("increment");
 
         let world = app.world();
@@ -554,7 +1181,7 @@ mod tests {
         let binding = binding_map
             .get("increment")
             .expect("Binding should exist")
-            .clone();
+            .create();
 
         // Call multiple times
         for i in 0..5 {
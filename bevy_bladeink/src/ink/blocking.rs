@@ -0,0 +1,183 @@
+use std::{cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use bevy::prelude::*;
+use bevy_crossbeam_event::CrossbeamEventSender;
+use bladeink::{story::external_functions::ExternalFunction, value_type::ValueType};
+
+use crate::{
+    ink::{InkBindingDefinition, InkValue},
+    resources::InkStoryId,
+    runtime::{InkDeferredEffects, InkEffects, trigger_binding_event},
+};
+
+/// Identifies a suspended story, handed out through
+/// [`SequenceSuspended`](crate::events::SequenceSuspended) and used to resume
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub struct InkResumeToken {
+    pub(crate) story: InkStoryId,
+    pub(crate) id: u64,
+}
+
+impl InkResumeToken {
+    /// The story which was suspended.
+    pub fn story(&self) -> InkStoryId {
+        self.story
+    }
+}
+
+/// A story waiting on a blocking binding.
+#[derive(Clone)]
+pub(crate) struct InkSuspension {
+    pub(crate) token: InkResumeToken,
+    pub(crate) flow: Option<String>,
+    pub(crate) function: String,
+    /// The values returned by the blocking calls resumed so far, in the order
+    /// the story makes them.
+    pub(crate) resolved: Vec<Option<InkValue>>,
+    /// The values returned by the other bindings called on the line before it
+    /// was suspended, returned again instead of calling them once more when
+    /// the line is continued.
+    pub(crate) replayed: Vec<Option<ValueType>>,
+    pub(crate) resumed: bool,
+}
+
+/// The blocking call a story was suspended on.
+pub(crate) struct BlockingCall {
+    pub(crate) function: String,
+    /// Triggers the event of the binding.
+    pub(crate) event: InkEffects,
+    /// The values returned by the other bindings called on the line before
+    /// the story was suspended.
+    pub(crate) replayed: Vec<Option<ValueType>>,
+}
+
+/// The blocking calls made while continuing the story.
+#[derive(Default)]
+struct BlockingCalls {
+    active: bool,
+    resolved: VecDeque<Option<InkValue>>,
+    replay: VecDeque<Option<ValueType>>,
+    recorded: Vec<Option<ValueType>>,
+    suspended: Option<BlockingCall>,
+}
+
+thread_local! {
+    static BLOCKING_CALLS: RefCell<BlockingCalls> = RefCell::new(BlockingCalls::default());
+}
+
+/// Runs `f`, answering blocking calls with the resolved values in order, and
+/// the other bindings with the replayed values until they run out. Returns the
+/// call the story was suspended on, if it ran out of resolved values.
+pub(crate) fn with_blocking_calls<R>(
+    resolved: &[Option<InkValue>],
+    replayed: &[Option<ValueType>],
+    f: impl FnOnce() -> R,
+) -> (R, Option<BlockingCall>) {
+    BLOCKING_CALLS.set(BlockingCalls {
+        active: true,
+        resolved: resolved.iter().cloned().collect(),
+        replay: replayed.iter().cloned().collect(),
+        recorded: Vec::new(),
+        suspended: None,
+    });
+    let result = f();
+    let calls = BLOCKING_CALLS.take();
+    let suspended = calls.suspended.map(|mut call| {
+        call.replayed = calls.recorded;
+        call
+    });
+    (result, suspended)
+}
+
+/// Calls a binding with side effects, e.g. a system binding, at most once for
+/// each line. A line suspended by a blocking binding is rolled back and run
+/// again once resumed, so the values returned the first time are replayed
+/// instead of calling the binding again.
+pub(crate) fn call_once(call: impl FnOnce() -> Option<ValueType>) -> Option<ValueType> {
+    let replayed = BLOCKING_CALLS.with_borrow_mut(|calls| {
+        if !calls.active {
+            return None;
+        }
+        // the line is run again once resumed, calls after the suspension are
+        // made then
+        if calls.suspended.is_some() {
+            return Some(Some(ValueType::Int(0)));
+        }
+        let value = calls.replay.pop_front()?;
+        calls.recorded.push(value.clone());
+        Some(value)
+    });
+    if let Some(value) = replayed {
+        return value;
+    }
+    let value = call();
+    BLOCKING_CALLS.with_borrow_mut(|calls| {
+        if calls.active && calls.suspended.is_none() {
+            calls.recorded.push(value.clone());
+        }
+    });
+    value
+}
+
+/// Ink binding which suspends the story when called, until it is resumed.
+pub(crate) struct InkBlockingFn<B: InkBindingDefinition> {
+    sender: CrossbeamEventSender<InkDeferredEffects>,
+    binding: PhantomData<B>,
+}
+
+impl<B: InkBindingDefinition> InkBlockingFn<B>
+where
+    for<'a> <B::Event as Event>::Trigger<'a>: Default,
+{
+    pub(crate) fn to_binding(
+        sender: CrossbeamEventSender<InkDeferredEffects>,
+    ) -> Rc<RefCell<dyn ExternalFunction>> {
+        Rc::new(RefCell::new(Self {
+            sender,
+            binding: PhantomData,
+        }))
+    }
+}
+
+impl<B: InkBindingDefinition> ExternalFunction for InkBlockingFn<B>
+where
+    for<'a> <B::Event as Event>::Trigger<'a>: Default,
+{
+    fn call(&mut self, name: &str, args: Vec<ValueType>) -> Option<ValueType> {
+        BLOCKING_CALLS.with_borrow_mut(|calls| {
+            // the story is rolled back once suspended, later calls are moot
+            if calls.suspended.is_some() {
+                return Some(ValueType::Int(0));
+            }
+            if let Some(value) = calls.resolved.pop_front() {
+                return value.as_ref().and_then(InkValue::to_value_type);
+            }
+            let event = match B::try_parse_event(&args[..]) {
+                Ok(event) => event,
+                Err(err) => {
+                    error!("Failed to invoke ink binding '{name}': {err:?}");
+                    return None;
+                }
+            };
+            if !calls.active {
+                warn!(
+                    "Ink binding '{name}' can only suspend the story while continuing a sequence"
+                );
+                trigger_binding_event(&self.sender, event);
+                return None;
+            }
+            let mut effects = InkEffects::default();
+            effects.trigger(event);
+            calls.suspended = Some(BlockingCall {
+                function: name.to_string(),
+                event: effects,
+                replayed: Vec::new(),
+            });
+            // the story is rolled back, but must make it to the end of the
+            // line, so a placeholder stands in for the return value in case
+            // it is assigned
+            Some(ValueType::Int(0))
+        })
+    }
+}
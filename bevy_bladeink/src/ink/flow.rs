@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bladeink::story::Story;
+use serde::{Deserialize, Serialize};
+
+/// Name bladeink uses internally for the default flow.
+const DEFAULT_FLOW_NAME: &str = "DEFAULT_FLOW";
+
+/// The named flows open in a story. Each flow has its own call stack, so
+/// every open flow keeps its position while another flow is running.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub struct InkFlows {
+    pub(crate) current: Option<String>,
+    pub(crate) open: Vec<String>,
+}
+
+impl InkFlows {
+    /// The flow the story is currently running, `None` for the default flow.
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Every named flow currently open in the story, including the current one.
+    pub fn open(&self) -> &[String] {
+        &self.open
+    }
+
+    /// Returns `true` if the named flow is open in the story.
+    pub fn contains(&self, flow: &str) -> bool {
+        self.open.iter().any(|open| open == flow)
+    }
+
+    pub(crate) fn switch(&mut self, flow: Option<&str>) {
+        self.current = flow.map(ToString::to_string);
+        if let Some(flow) = flow
+            && !self.contains(flow)
+        {
+            self.open.push(flow.to_string());
+        }
+    }
+
+    pub(crate) fn remove(&mut self, flow: &str) {
+        self.open.retain(|open| open != flow);
+        if self.current.as_deref() == Some(flow) {
+            self.current = None;
+        }
+    }
+
+    /// Reads the flows from a state serialized with `Story::save_state`.
+    pub(crate) fn from_serialized_state(state: &str) -> Self {
+        let Ok(state) = serde_json::from_str::<serde_json::Value>(state) else {
+            return Self::default();
+        };
+
+        let current = state
+            .get("currentFlowName")
+            .and_then(serde_json::Value::as_str)
+            .filter(|name| *name != DEFAULT_FLOW_NAME)
+            .map(ToString::to_string);
+
+        let open = state
+            .get("flows")
+            .and_then(serde_json::Value::as_object)
+            .map(|flows| {
+                flows
+                    .keys()
+                    .filter(|name| *name != DEFAULT_FLOW_NAME)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self { current, open }
+    }
+
+    pub(crate) fn from_story(story: &Story) -> Self {
+        story
+            .save_state()
+            .map(|state| Self::from_serialized_state(&state))
+            .unwrap_or_default()
+    }
+}
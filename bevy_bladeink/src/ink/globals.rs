@@ -0,0 +1,319 @@
+//! ## Ink Globals
+//!
+//! The global variables of a story can be mirrored into a typed resource,
+//! instead of reading them from [`InkVariables`](crate::resources::InkVariables)
+//! by name:
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_bladeink::prelude::*;
+//!
+//! #[derive(Resource, Default, InkGlobals)]
+//! struct Intercept {
+//!     forceful: i32,
+//!     #[ink(name = "teacup")]
+//!     has_teacup: bool,
+//! }
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins(DefaultPlugins)
+//!         .add_plugins(InkPlugin)
+//!         .init_ink_globals::<Intercept>()
+//!         .run();
+//! }
+//! ```
+//!
+//! Every field is tracked once the story is ready, and kept up to date as the
+//! story changes the variables. A field mirroring a variable the story doesn't
+//! declare, or declares with another type, is reported as an error.
+//!
+//! A single variable can be observed instead, running a system whenever the
+//! story changes it:
+//!
+//! ```rust,ignore
+//! fn on_forceful(In(change): In<InkVariableChange<i32>>) {
+//!     info!("forceful went from {:?} to {}", change.old, change.new);
+//! }
+//!
+//! app.observe_ink_variable("forceful", on_forceful);
+//! ```
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy_crossbeam_event::CrossbeamEventSender;
+
+use crate::{
+    commands::{TrackVariableCommand, VariableUpdated, observe_variable},
+    events::{InkVariableChanged, StoryReady, StoryReloaded},
+    ink::{InkList, InkValue, InkValueKind},
+    resources::InkStoryId,
+    runtime::{InkEffects, run_ink},
+};
+
+/// Resources mirroring the global variables of a story, usually implemented
+/// with `#[derive(InkGlobals)]`.
+pub trait InkGlobals: Resource {
+    /// The names of the mirrored variables, with the type of each.
+    fn variables() -> Vec<(&'static str, InkValueKind)>;
+
+    /// Updates the field mirroring the variable, returning whether it changed.
+    fn set_variable(&mut self, name: &str, value: &InkValue) -> bool;
+}
+
+/// Types which can mirror an ink variable in an [`InkGlobals`] resource.
+pub trait FromInkValue: PartialEq + Sized {
+    /// The type of the ink values the type is read from.
+    const KIND: InkValueKind;
+
+    fn from_ink_value(value: &InkValue) -> Option<Self>;
+}
+
+impl FromInkValue for bool {
+    const KIND: InkValueKind = InkValueKind::Bool;
+
+    fn from_ink_value(value: &InkValue) -> Option<Self> {
+        value.get_bool()
+    }
+}
+
+impl FromInkValue for i32 {
+    const KIND: InkValueKind = InkValueKind::Int;
+
+    fn from_ink_value(value: &InkValue) -> Option<Self> {
+        value.get_int()
+    }
+}
+
+impl FromInkValue for f32 {
+    const KIND: InkValueKind = InkValueKind::Float;
+
+    fn from_ink_value(value: &InkValue) -> Option<Self> {
+        value.get_float()
+    }
+}
+
+impl FromInkValue for String {
+    const KIND: InkValueKind = InkValueKind::String;
+
+    fn from_ink_value(value: &InkValue) -> Option<Self> {
+        value.get_string().cloned()
+    }
+}
+
+impl FromInkValue for InkList {
+    const KIND: InkValueKind = InkValueKind::List;
+
+    fn from_ink_value(value: &InkValue) -> Option<Self> {
+        value.get_list().cloned()
+    }
+}
+
+/// Allows mirroring the global variables of a story into a resource.
+pub trait AddInkGlobalsApp {
+    /// Mirrors the variables of the default story into the resource.
+    fn init_ink_globals<T: InkGlobals + Default>(&mut self) -> &mut Self;
+
+    /// Mirrors the variables of a single story into the resource.
+    fn init_ink_globals_for<T: InkGlobals + Default>(&mut self, story: InkStoryId) -> &mut Self;
+}
+
+impl AddInkGlobalsApp for App {
+    fn init_ink_globals<T: InkGlobals + Default>(&mut self) -> &mut Self {
+        self.init_ink_globals_for::<T>(InkStoryId::DEFAULT)
+    }
+
+    fn init_ink_globals_for<T: InkGlobals + Default>(&mut self, story: InkStoryId) -> &mut Self {
+        self.init_resource::<T>()
+            .add_observer(move |ready: On<StoryReady>, mut commands: Commands| {
+                if ready.story == story {
+                    commands.queue(TrackGlobalsCommand::<T>::new(story));
+                }
+            })
+            .add_observer(move |reloaded: On<StoryReloaded>, mut commands: Commands| {
+                if reloaded.story == story {
+                    commands.queue(TrackGlobalsCommand::<T>::new(story));
+                }
+            })
+            .add_observer(
+                move |variable: On<VariableUpdated>, globals: Option<ResMut<T>>| {
+                    let Some(mut globals) = globals else {
+                        return;
+                    };
+                    if variable.story != story {
+                        return;
+                    }
+                    if globals
+                        .bypass_change_detection()
+                        .set_variable(&variable.name, &variable.value)
+                    {
+                        globals.set_changed();
+                    }
+                },
+            )
+    }
+}
+
+/// A change of a variable observed through [`ObserveInkVariableApp`], read
+/// as `T`.
+#[derive(Clone, Debug)]
+pub struct InkVariableChange<T> {
+    pub story: InkStoryId,
+    pub name: String,
+    /// The previous value of the variable, if it was known and read as `T`.
+    pub old: Option<T>,
+    pub new: T,
+}
+
+/// Allows running systems when a story changes a variable.
+pub trait ObserveInkVariableApp {
+    /// Runs the system whenever the default story changes the variable, which
+    /// is tracked once the story is ready.
+    fn observe_ink_variable<T, M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<In<InkVariableChange<T>>, (), M> + 'static,
+    ) -> &mut Self
+    where
+        T: FromInkValue + Send + Sync + 'static;
+
+    /// Runs the system whenever a single story changes the variable.
+    fn observe_ink_variable_for<T, M>(
+        &mut self,
+        story: InkStoryId,
+        name: impl Into<String>,
+        system: impl IntoSystem<In<InkVariableChange<T>>, (), M> + 'static,
+    ) -> &mut Self
+    where
+        T: FromInkValue + Send + Sync + 'static;
+}
+
+impl ObserveInkVariableApp for App {
+    fn observe_ink_variable<T, M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<In<InkVariableChange<T>>, (), M> + 'static,
+    ) -> &mut Self
+    where
+        T: FromInkValue + Send + Sync + 'static,
+    {
+        self.observe_ink_variable_for(InkStoryId::DEFAULT, name, system)
+    }
+
+    fn observe_ink_variable_for<T, M>(
+        &mut self,
+        story: InkStoryId,
+        name: impl Into<String>,
+        system: impl IntoSystem<In<InkVariableChange<T>>, (), M> + 'static,
+    ) -> &mut Self
+    where
+        T: FromInkValue + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let system = self.register_system(system);
+        let tracked = name.clone();
+        self.add_observer(move |ready: On<StoryReady>, mut commands: Commands| {
+            if ready.story == story {
+                commands.queue(TrackVariableCommand::new(story, tracked.clone()));
+            }
+        })
+        .add_observer(
+            move |changed: On<InkVariableChanged>, mut commands: Commands| {
+                if changed.story != story || changed.name != name {
+                    return;
+                }
+                let Some(new) = T::from_ink_value(&changed.new) else {
+                    warn!(
+                        "Ink variable '{name}' changed to {:?}, expected {:?}",
+                        changed.new,
+                        T::KIND
+                    );
+                    return;
+                };
+                commands.run_system_with(
+                    system,
+                    InkVariableChange {
+                        story,
+                        name: name.clone(),
+                        old: changed.old.as_ref().and_then(T::from_ink_value),
+                        new,
+                    },
+                );
+            },
+        )
+    }
+}
+
+/// Tracks the variables mirrored by an [`InkGlobals`] resource, checking the
+/// story declares each of them with the right type.
+struct TrackGlobalsCommand<T> {
+    story: InkStoryId,
+    globals: PhantomData<T>,
+}
+
+impl<T> TrackGlobalsCommand<T> {
+    fn new(story: InkStoryId) -> Self {
+        Self {
+            story,
+            globals: PhantomData,
+        }
+    }
+}
+
+impl<T: InkGlobals> Command for TrackGlobalsCommand<T> {
+    fn apply(self, world: &mut World) {
+        let Some(sender) = world
+            .get_resource::<CrossbeamEventSender<VariableUpdated>>()
+            .cloned()
+        else {
+            error!(
+                "Failed to track ink globals: CrossbeamEventSender resource not found. Did you forget to initialize the InkPlugin?"
+            );
+            return;
+        };
+        let story_id = self.story;
+        let globals = std::any::type_name::<T>();
+        let variables = T::variables();
+
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            if !stories.contains(story_id) {
+                error!("Failed to track ink globals: Story {story_id:?} not found.");
+                return effects;
+            }
+
+            // variables tracked before a reload are observed again already
+            let tracked = stories.tracked(story_id);
+            for (name, kind) in variables {
+                let value = if tracked.iter().any(|tracked| tracked == name) {
+                    stories
+                        .get(story_id)
+                        .and_then(|story| story.get_variable(name))
+                        .map(InkValue::try_from)
+                        .transpose()
+                } else {
+                    observe_variable(stories, story_id, name, sender.clone())
+                };
+                match value {
+                    Ok(Some(value)) if value.kind() == kind => {
+                        effects.trigger(VariableUpdated {
+                            story: story_id,
+                            name: name.to_string(),
+                            value,
+                        });
+                    }
+                    Ok(Some(value)) => error!(
+                        "Ink global '{name}' of {globals} is {:?} in story {story_id:?}, expected {kind:?}",
+                        value.kind()
+                    ),
+                    Ok(None) => {
+                        error!("Ink global '{name}' of {globals} not found in story {story_id:?}");
+                    }
+                    Err(err) => error!("Failed to track ink global '{name}' of {globals}: {err}"),
+                }
+            }
+            effects
+        });
+    }
+}
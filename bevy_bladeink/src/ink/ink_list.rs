@@ -0,0 +1,536 @@
+//! Ink `LIST` values, with the value semantics necessary for use in bevy.
+
+use std::{cell::RefCell, fmt};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bladeink::{
+    story::{INK_VERSION_CURRENT, Story},
+    story_error::StoryError,
+    value_type::ValueType,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// The `LIST`s declared by a story, with the value of each of their items.
+pub(crate) type InkListDefinitions = HashMap<String, HashMap<String, i32>>;
+
+/// An item of an ink `LIST`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub struct InkListItem {
+    /// The name of the `LIST` declaring the item.
+    pub origin: String,
+    pub name: String,
+    /// The value of the item, used to order the items of a list.
+    pub value: i32,
+}
+
+impl InkListItem {
+    pub fn new(origin: impl Into<String>, name: impl Into<String>, value: i32) -> Self {
+        Self {
+            origin: origin.into(),
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// The name of the item qualified by its origin, e.g. `Inventory.key`.
+    pub fn full_name(&self) -> String {
+        format!("{}.{}", self.origin, self.name)
+    }
+
+    /// Whether the item has the given name, either on its own, or qualified
+    /// by its origin.
+    fn is_named(&self, name: &str) -> bool {
+        match name.split_once('.') {
+            Some((origin, name)) => self.origin == origin && self.name == name,
+            None => self.name == name,
+        }
+    }
+}
+
+impl fmt::Display for InkListItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// An ink `LIST` value, e.g. an inventory. Its items are ordered by value,
+/// the same way ink orders them.
+///
+/// Lists read from a story also know every item of the `LIST`s they come from,
+/// see [`InkList::all_items`]. Lists built in Bevy don't, and are only valid
+/// in a story declaring the `LIST`s their items come from, which they take
+/// every item of, and the values of their items from, once set in it.
+#[derive(Clone, Debug, Default, Reflect, Serialize, Deserialize)]
+pub struct InkList {
+    items: Vec<InkListItem>,
+    origins: Vec<String>,
+    all: Vec<InkListItem>,
+}
+
+impl InkList {
+    /// Creates an empty list, without any origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty list of the given `LIST`, e.g. to clear an inventory.
+    pub fn empty_of(origin: impl Into<String>) -> Self {
+        Self {
+            origins: vec![origin.into()],
+            ..Self::default()
+        }
+    }
+
+    /// Creates a list holding the given items.
+    pub fn from_items(items: impl IntoIterator<Item = InkListItem>) -> Self {
+        let mut list = Self::new();
+        for item in items {
+            list.insert(item);
+        }
+        list
+    }
+
+    /// The items of the list, ordered by value.
+    pub fn items(&self) -> &[InkListItem] {
+        &self.items
+    }
+
+    /// The names of the `LIST`s the items of the list come from.
+    pub fn origin_names(&self) -> &[String] {
+        &self.origins
+    }
+
+    /// Every item of the `LIST`s the list comes from, ordered by value. Empty
+    /// for lists built in Bevy.
+    pub fn all_items(&self) -> &[InkListItem] {
+        &self.all
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Whether the list holds an item with the given name, either on its own,
+    /// or qualified by its origin, e.g. `Inventory.key`.
+    pub fn contains_item(&self, name: &str) -> bool {
+        self.items.iter().any(|item| item.is_named(name))
+    }
+
+    /// Whether the list holds every item of the other one. Like in ink, no
+    /// list contains an empty one.
+    pub fn contains(&self, other: &InkList) -> bool {
+        !other.is_empty() && other.items.iter().all(|item| self.items.contains(item))
+    }
+
+    /// The item with the lowest value.
+    pub fn min(&self) -> Option<&InkListItem> {
+        self.items.first()
+    }
+
+    /// The item with the highest value.
+    pub fn max(&self) -> Option<&InkListItem> {
+        self.items.last()
+    }
+
+    /// Adds an item to the list, if it doesn't hold it yet.
+    pub fn insert(&mut self, item: InkListItem) {
+        if self.items.contains(&item) {
+            return;
+        }
+        if !self.origins.contains(&item.origin) {
+            self.origins.push(item.origin.clone());
+        }
+        self.items.push(item);
+        sort_items(&mut self.items);
+    }
+
+    /// Removes the item with the given name, either on its own, or qualified
+    /// by its origin. Returns the removed item, if any.
+    pub fn remove(&mut self, name: &str) -> Option<InkListItem> {
+        let index = self.items.iter().position(|item| item.is_named(name))?;
+        Some(self.items.remove(index))
+    }
+
+    /// The items of both lists, the same as ink's `+`.
+    pub fn union(&self, other: &InkList) -> InkList {
+        let mut union = self.clone();
+        for item in &other.items {
+            union.insert(item.clone());
+        }
+        for origin in &other.origins {
+            if !union.origins.contains(origin) {
+                union.origins.push(origin.clone());
+            }
+        }
+        union.merge_all(other);
+        union
+    }
+
+    /// The items held by both lists, the same as ink's `^`.
+    pub fn intersection(&self, other: &InkList) -> InkList {
+        let mut intersection = self.clone();
+        intersection.items.retain(|item| other.items.contains(item));
+        intersection
+    }
+
+    /// The items of the list which the other one doesn't hold, the same as
+    /// ink's `-`.
+    pub fn without(&self, other: &InkList) -> InkList {
+        let mut without = self.clone();
+        without.items.retain(|item| !other.items.contains(item));
+        without
+    }
+
+    /// Every item of the origins of the list which it doesn't hold, the same
+    /// as ink's `LIST_INVERT`. Only available for lists read from a story.
+    pub fn inverse(&self) -> InkList {
+        InkList {
+            items: self
+                .all
+                .iter()
+                .filter(|item| !self.items.contains(item))
+                .cloned()
+                .collect(),
+            origins: self.origins.clone(),
+            all: self.all.clone(),
+        }
+    }
+
+    fn merge_all(&mut self, other: &InkList) {
+        for item in &other.all {
+            if !self.all.contains(item) {
+                self.all.push(item.clone());
+            }
+        }
+        sort_items(&mut self.all);
+    }
+}
+
+fn sort_items(items: &mut [InkListItem]) {
+    items.sort_by(|a, b| a.value.cmp(&b.value).then_with(|| a.origin.cmp(&b.origin)));
+}
+
+/// Lists are equal when they hold the same items, like in ink.
+impl PartialEq for InkList {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl fmt::Display for InkList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<InkListItem> for InkList {
+    fn from_iter<I: IntoIterator<Item = InkListItem>>(items: I) -> Self {
+        Self::from_items(items)
+    }
+}
+
+impl InkList {
+    /// Reads the list held by an ink value, if it holds one.
+    pub(crate) fn from_value(value: &ValueType) -> Option<InkList> {
+        let ValueType::List(list) = value else {
+            return None;
+        };
+        let mut items: Vec<InkListItem> = list
+            .items
+            .iter()
+            .filter_map(|(item, value)| {
+                Some(InkListItem::new(
+                    item.get_origin_name()?.clone(),
+                    item.get_item_name(),
+                    *value,
+                ))
+            })
+            .collect();
+        sort_items(&mut items);
+
+        let mut all = Vec::new();
+        for origin in list.origins.borrow().iter() {
+            let mut origin = origin.clone();
+            let origin_items = origin.get_items().iter().filter_map(|(item, value)| {
+                Some(InkListItem::new(
+                    item.get_origin_name()?.clone(),
+                    item.get_item_name(),
+                    *value,
+                ))
+            });
+            all.extend(origin_items);
+        }
+        sort_items(&mut all);
+
+        let mut origins = list.get_origin_names();
+        origins.sort();
+        origins.dedup();
+        Some(Self {
+            items,
+            origins,
+            all,
+        })
+    }
+
+    /// Creates the ink value of the list, with origins only knowing the items
+    /// the list knows of. Used where the story it ends up in isn't known, e.g.
+    /// for the return values of bindings.
+    pub(crate) fn to_value(&self) -> Result<ValueType, StoryError> {
+        let mut definitions: serde_json::Map<String, Value> = self
+            .origins
+            .iter()
+            .map(|origin| (origin.clone(), Value::Object(Default::default())))
+            .collect();
+        for item in self.items.iter().chain(&self.all) {
+            if let Some(Value::Object(items)) = definitions.get_mut(&item.origin) {
+                // definitions only hold positive values
+                items.insert(item.name.clone(), json!(item.value.max(0)));
+            }
+        }
+        self.list_value(definitions)
+    }
+
+    /// Creates the ink value of the list for a story, whose `LIST`s give the
+    /// list its origins, e.g. for `LIST_ALL`.
+    pub(crate) fn to_story_value(
+        &self,
+        lists: &InkListDefinitions,
+    ) -> Result<ValueType, StoryError> {
+        let mut definitions = serde_json::Map::new();
+        for origin in self
+            .origins
+            .iter()
+            .chain(self.items.iter().map(|item| &item.origin))
+        {
+            let Some(items) = lists.get(origin) else {
+                return Err(StoryError::InvalidStoryState(format!(
+                    "LIST '{origin}' is not declared by the story"
+                )));
+            };
+            definitions.insert(origin.clone(), json!(items));
+        }
+        // the story decides the values of the items
+        let mut list = self.clone();
+        for item in &mut list.items {
+            let Some(value) = lists[&item.origin].get(&item.name) else {
+                return Err(StoryError::InvalidStoryState(format!(
+                    "'{}' is not an item of LIST '{}'",
+                    item.name, item.origin
+                )));
+            };
+            item.value = *value;
+        }
+        list.list_value(definitions)
+    }
+
+    fn list_value(
+        &self,
+        definitions: serde_json::Map<String, Value>,
+    ) -> Result<ValueType, StoryError> {
+        let items: serde_json::Map<String, Value> = self
+            .items
+            .iter()
+            .map(|item| (item.full_name(), json!(item.value)))
+            .collect();
+        let list = json!({ "list": items, "origins": self.origins });
+        with_list_story(definitions, |list_story| {
+            let mut state = list_story.state.clone();
+            state["variablesState"]["list"] = list;
+            list_story.story.load_state(&state.to_string())?;
+            // loading the state doesn't resolve the origins of the list, which
+            // the story does when evaluating it
+            list_story
+                .story
+                .evaluate_function("read", None, &mut String::new())?
+                .ok_or_else(|| {
+                    StoryError::InvalidStoryState("Failed to create ink list".to_string())
+                })
+        })
+    }
+}
+
+/// A story declaring a single global list variable, `list`, and a function,
+/// `read`, returning it, along with the `LIST`s of its origins and its initial
+/// state.
+///
+/// bladeink doesn't expose a way to build lists, so lists are loaded into the
+/// state of the story and read back from it.
+struct ListStory {
+    story: Story,
+    state: Value,
+}
+
+impl ListStory {
+    fn new(definitions: serde_json::Map<String, Value>) -> Result<Self, StoryError> {
+        let json = json!({
+            "inkVersion": INK_VERSION_CURRENT,
+            "root": [
+                ["done", null],
+                "done",
+                {
+                    "global decl": [
+                        "ev",
+                        { "list": {} },
+                        { "VAR=": "list" },
+                        "/ev",
+                        "end",
+                        null
+                    ],
+                    "read": ["ev", { "VAR?": "list" }, "/ev", "~ret", null]
+                }
+            ],
+            "listDefs": definitions,
+        });
+        let story = Story::new(&json.to_string())?;
+        let state = serde_json::from_str(&story.save_state()?)
+            .map_err(|err| StoryError::InvalidStoryState(err.to_string()))?;
+        Ok(Self { story, state })
+    }
+}
+
+/// The number of list stories kept for each thread, past which they are
+/// parsed again.
+const MAX_LIST_STORIES: usize = 32;
+
+thread_local! {
+    /// Parsed once per thread and set of `LIST`s, as `Story` is neither
+    /// `Send` nor `Sync`.
+    static LIST_STORIES: RefCell<HashMap<String, ListStory>> = RefCell::new(HashMap::default());
+}
+
+/// Runs `f` with the list story of the current thread declaring the `LIST`s,
+/// creating it if needed.
+fn with_list_story<T>(
+    definitions: serde_json::Map<String, Value>,
+    f: impl FnOnce(&mut ListStory) -> Result<T, StoryError>,
+) -> Result<T, StoryError> {
+    // the keys of the definitions are sorted, so equal sets share a story
+    let key = Value::Object(definitions.clone()).to_string();
+    LIST_STORIES.with_borrow_mut(|list_stories| {
+        if !list_stories.contains_key(&key) {
+            if list_stories.len() >= MAX_LIST_STORIES {
+                list_stories.clear();
+            }
+            list_stories.insert(key.clone(), ListStory::new(definitions)?);
+        }
+        f(list_stories
+            .get_mut(&key)
+            .expect("The list story was just created"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory(items: &[(&str, i32)]) -> InkList {
+        items
+            .iter()
+            .map(|(name, value)| InkListItem::new("Inventory", *name, *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_items_are_ordered_by_value() {
+        let list = inventory(&[("sword", 3), ("key", 1), ("map", 2)]);
+        let names: Vec<_> = list.items().iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["key", "map", "sword"]);
+        assert_eq!(list.min().map(|item| item.value), Some(1));
+        assert_eq!(list.max().map(|item| item.value), Some(3));
+        assert_eq!(list.origin_names(), ["Inventory"]);
+        assert_eq!(list.to_string(), "key, map, sword");
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = inventory(&[("key", 1), ("map", 2)]);
+        let b = inventory(&[("map", 2), ("sword", 3)]);
+
+        assert_eq!(
+            a.union(&b),
+            inventory(&[("key", 1), ("map", 2), ("sword", 3)])
+        );
+        assert_eq!(a.intersection(&b), inventory(&[("map", 2)]));
+        assert_eq!(a.without(&b), inventory(&[("key", 1)]));
+        assert!(a.contains(&inventory(&[("key", 1)])));
+        assert!(!a.contains(&b));
+        assert!(!a.contains(&InkList::new()));
+        assert!(a.contains_item("key"));
+        assert!(a.contains_item("Inventory.key"));
+        assert!(!a.contains_item("Weapons.key"));
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut list = InkList::empty_of("Inventory");
+        assert!(list.is_empty());
+        list.insert(InkListItem::new("Inventory", "key", 1));
+        list.insert(InkListItem::new("Inventory", "key", 1));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.remove("key").map(|item| item.value), Some(1));
+        assert!(list.remove("key").is_none());
+        assert_eq!(list.origin_names(), ["Inventory"]);
+    }
+
+    #[test]
+    fn test_round_trip_through_ink_value() {
+        let list = inventory(&[("key", 1), ("map", 2)]);
+        let value = list.to_value().unwrap();
+        let read = InkList::from_value(&value).unwrap();
+        assert_eq!(read, list);
+        assert_eq!(read.origin_names(), ["Inventory"]);
+
+        let empty = InkList::empty_of("Inventory").to_value().unwrap();
+        let read = InkList::from_value(&empty).unwrap();
+        assert!(read.is_empty());
+        assert_eq!(read.origin_names(), ["Inventory"]);
+    }
+
+    #[test]
+    fn test_story_value_takes_the_lists_of_the_story() {
+        let lists = InkListDefinitions::from([(
+            "Inventory".to_string(),
+            HashMap::from([
+                ("key".to_string(), 1),
+                ("map".to_string(), 2),
+                ("sword".to_string(), 3),
+            ]),
+        )]);
+        let value = inventory(&[("map", 0)]).to_story_value(&lists).unwrap();
+        let read = InkList::from_value(&value).unwrap();
+        assert_eq!(read, inventory(&[("map", 2)]));
+        assert_eq!(read.all_items().len(), 3);
+
+        assert!(inventory(&[("shield", 4)]).to_story_value(&lists).is_err());
+        let mood = InkList::from_items([InkListItem::new("Mood", "calm", 1)]);
+        assert!(mood.to_story_value(&lists).is_err());
+    }
+
+    #[test]
+    fn test_lists_of_the_same_lists_share_a_story() {
+        let lists = InkListDefinitions::from([(
+            "Inventory".to_string(),
+            HashMap::from([("key".to_string(), 1), ("map".to_string(), 2)]),
+        )]);
+        let stories = || LIST_STORIES.with_borrow(HashMap::len);
+        inventory(&[("key", 1)]).to_story_value(&lists).unwrap();
+        let created = stories();
+        let value = inventory(&[("map", 2)]).to_story_value(&lists).unwrap();
+        assert_eq!(stories(), created);
+        assert_eq!(
+            InkList::from_value(&value).unwrap(),
+            inventory(&[("map", 2)])
+        );
+    }
+}
@@ -2,28 +2,77 @@
 //! `bladeink::value_type::ValueType`, but with the value semantics necessary
 //! for use in bevy.
 
+use std::cell::RefCell;
+
 use bevy::prelude::*;
-use bladeink::value_type::ValueType;
+use bladeink::{
+    story::{INK_VERSION_CURRENT, Story},
+    story_error::StoryError,
+    value_type::ValueType,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use crate::ink::{InkList, InkListDefinitions};
 
 /// An Ink value, tagged with its type.
 #[repr(u8)]
-#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 pub enum InkValue {
     Bool(bool),
     Int(i32),
     Float(f32),
     /// An Ink list value.
-    List,
+    List(InkList),
     /// Ink string, constructed with [`new_string`](ValueType::new::<&str>)
     String(String),
-    /// Reference to an Ink divert.
-    DivertTarget,
+    /// Reference to an Ink divert, holding the path of its target, e.g.
+    /// `knot.stitch`.
+    DivertTarget(String),
     /// Reference to an Ink variable.
+    VariablePointer {
+        name: String,
+        /// Where the variable lives: `0` for globals, the index of the
+        /// callstack element plus one for temporaries, and `-1` when it is yet
+        /// to be resolved.
+        context_index: i32,
+    },
+}
+
+/// The type of an [`InkValue`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum InkValueKind {
+    Bool,
+    Int,
+    Float,
+    List,
+    String,
+    DivertTarget,
     VariablePointer,
 }
 
+/// Error converting an [`InkValue`] into a value of the runtime.
+#[derive(Debug, Error)]
+#[error("Could not convert {value:?} into an ink value: {source}")]
+pub struct InkValueError {
+    pub value: InkValue,
+    pub source: StoryError,
+}
+
 impl InkValue {
+    pub fn kind(&self) -> InkValueKind {
+        match self {
+            InkValue::Bool(_) => InkValueKind::Bool,
+            InkValue::Int(_) => InkValueKind::Int,
+            InkValue::Float(_) => InkValueKind::Float,
+            InkValue::List(_) => InkValueKind::List,
+            InkValue::String(_) => InkValueKind::String,
+            InkValue::DivertTarget(_) => InkValueKind::DivertTarget,
+            InkValue::VariablePointer { .. } => InkValueKind::VariablePointer,
+        }
+    }
+
     pub fn get_bool(&self) -> Option<bool> {
         match self {
             InkValue::Bool(b) => Some(*b),
@@ -51,6 +100,52 @@ impl InkValue {
             _ => None,
         }
     }
+
+    pub fn get_list(&self) -> Option<&InkList> {
+        match self {
+            InkValue::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    pub fn get_divert_target(&self) -> Option<&String> {
+        match self {
+            InkValue::DivertTarget(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Converts the value for a story declaring the given `LIST`s, which
+    /// lists take their origins from.
+    pub(crate) fn to_story_value_type(
+        &self,
+        lists: &InkListDefinitions,
+    ) -> Result<ValueType, InkValueError> {
+        match self {
+            InkValue::List(list) => list.to_story_value(lists).map_err(|source| InkValueError {
+                value: self.clone(),
+                source,
+            }),
+            value => ValueType::try_from(value),
+        }
+    }
+
+    /// Reads the arguments of an external function call, logging why they
+    /// can't be.
+    pub(crate) fn from_args(args: Vec<ValueType>) -> Option<Vec<InkValue>> {
+        args.into_iter()
+            .map(InkValue::try_from)
+            .collect::<Result<_, _>>()
+            .inspect_err(|err| error!("Failed to read the arguments of an ink function: {err}"))
+            .ok()
+    }
+
+    /// Converts the value for the runtime, logging why it can't be.
+    pub(crate) fn to_value_type(&self) -> Option<ValueType> {
+        ValueType::try_from(self)
+            .inspect_err(|err| error!("{err}"))
+            .ok()
+    }
 }
 
 impl From<bool> for InkValue {
@@ -83,30 +178,190 @@ impl From<i32> for InkValue {
     }
 }
 
-impl From<ValueType> for InkValue {
-    fn from(value_type: ValueType) -> Self {
-        match value_type {
+impl From<InkList> for InkValue {
+    fn from(l: InkList) -> Self {
+        InkValue::List(l)
+    }
+}
+
+impl TryFrom<ValueType> for InkValue {
+    type Error = StoryError;
+
+    fn try_from(value_type: ValueType) -> Result<Self, Self::Error> {
+        Ok(match value_type {
             ValueType::Bool(b) => InkValue::Bool(b),
             ValueType::Int(i) => InkValue::Int(i),
             ValueType::Float(f) => InkValue::Float(f),
             ValueType::String(s) => InkValue::String(s.string),
-            ValueType::List(_) => InkValue::List,
-            ValueType::DivertTarget(_) => InkValue::DivertTarget,
-            ValueType::VariablePointer(_) => InkValue::VariablePointer,
-        }
+            ValueType::List(_) => {
+                InkValue::List(InkList::from_value(&value_type).unwrap_or_default())
+            }
+            ValueType::DivertTarget(path) => InkValue::DivertTarget(path.get_components_string()),
+            ValueType::VariablePointer(_) => {
+                let pointer = value_to_json(&value_type)?;
+                let invalid = || {
+                    StoryError::InvalidStoryState(format!("Invalid variable pointer: {pointer}"))
+                };
+                InkValue::VariablePointer {
+                    name: pointer["^var"].as_str().ok_or_else(invalid)?.to_string(),
+                    context_index: pointer["ci"]
+                        .as_i64()
+                        .and_then(|index| i32::try_from(index).ok())
+                        .ok_or_else(invalid)?,
+                }
+            }
+        })
     }
 }
 
-impl From<&InkValue> for ValueType {
-    fn from(ink_value: &InkValue) -> Self {
-        match ink_value {
-            InkValue::Bool(b) => ValueType::Bool(*b),
-            InkValue::Int(i) => ValueType::Int(*i),
-            InkValue::Float(f) => ValueType::Float(*f),
-            InkValue::String(s) => ValueType::from(s.as_str()),
-            InkValue::List => todo!(),
-            InkValue::DivertTarget => todo!(),
-            InkValue::VariablePointer => todo!(),
-        }
+impl TryFrom<&InkValue> for ValueType {
+    type Error = InkValueError;
+
+    fn try_from(ink_value: &InkValue) -> Result<Self, Self::Error> {
+        let value = match ink_value {
+            InkValue::Bool(b) => Ok(ValueType::Bool(*b)),
+            InkValue::Int(i) => Ok(ValueType::Int(*i)),
+            InkValue::Float(f) => Ok(ValueType::Float(*f)),
+            InkValue::String(s) => Ok(ValueType::from(s.as_str())),
+            InkValue::List(l) => l.to_value(),
+            InkValue::DivertTarget(path) => value_from_json(json!({ "^->": path })),
+            InkValue::VariablePointer {
+                name,
+                context_index,
+            } => value_from_json(json!({ "^var": name, "ci": context_index })),
+        };
+        value.map_err(|source| InkValueError {
+            value: ink_value.clone(),
+            source,
+        })
+    }
+}
+
+/// A story declaring a single global variable, `value`, along with its
+/// initial state.
+struct ValueStory {
+    story: Story,
+    state: Value,
+}
+
+impl ValueStory {
+    fn new() -> Result<Self, StoryError> {
+        let json = json!({
+            "inkVersion": INK_VERSION_CURRENT,
+            "root": [
+                ["done", null],
+                "done",
+                { "global decl": ["ev", 0, { "VAR=": "value" }, "/ev", "end", null] }
+            ],
+            "listDefs": {},
+        });
+        let story = Story::new(&json.to_string())?;
+        let state = serde_json::from_str(&story.save_state()?)
+            .map_err(|err| StoryError::InvalidStoryState(err.to_string()))?;
+        Ok(Self { story, state })
+    }
+}
+
+thread_local! {
+    /// Parsed once per thread, as `Story` is neither `Send` nor `Sync`.
+    static VALUE_STORY: RefCell<Option<ValueStory>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with the story of the current thread, creating it if needed.
+fn with_value_story<T>(
+    f: impl FnOnce(&mut ValueStory) -> Result<T, StoryError>,
+) -> Result<T, StoryError> {
+    VALUE_STORY.with_borrow_mut(|value_story| {
+        let value_story = match value_story {
+            Some(value_story) => value_story,
+            None => value_story.insert(ValueStory::new()?),
+        };
+        f(value_story)
+    })
+}
+
+// bladeink keeps the payloads of divert targets and variable pointers private,
+// so they are read from, and created through, the saved state of a story
+// holding them in its only variable.
+
+fn value_to_json(value: &ValueType) -> Result<Value, StoryError> {
+    with_value_story(|value_story| {
+        value_story.story.set_variable("value", value)?;
+        let mut state: Value = serde_json::from_str(&value_story.story.save_state()?)
+            .map_err(|err| StoryError::InvalidStoryState(err.to_string()))?;
+        Ok(state["variablesState"]["value"].take())
+    })
+}
+
+fn value_from_json(value: Value) -> Result<ValueType, StoryError> {
+    with_value_story(|value_story| {
+        let mut state = value_story.state.clone();
+        state["variablesState"]["value"] = value;
+        value_story.story.load_state(&state.to_string())?;
+        value_story
+            .story
+            .get_variable("value")
+            .ok_or_else(|| StoryError::InvalidStoryState("Failed to create ink value".to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ink::InkListItem;
+
+    fn round_trip(value: InkValue) -> InkValue {
+        InkValue::try_from(ValueType::try_from(&value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_divert_target_round_trip() {
+        let value = round_trip(InkValue::DivertTarget("market.stall".to_string()));
+        assert_eq!(value.get_divert_target().unwrap(), "market.stall");
+    }
+
+    #[test]
+    fn test_variable_pointer_round_trip() {
+        let value = round_trip(InkValue::VariablePointer {
+            name: "gold".to_string(),
+            context_index: 0,
+        });
+        assert!(matches!(
+            value,
+            InkValue::VariablePointer { name, context_index: 0 } if name == "gold"
+        ));
+    }
+
+    #[test]
+    fn test_conversions_share_the_value_story() {
+        round_trip(InkValue::DivertTarget("market".to_string()));
+        let story = VALUE_STORY
+            .with_borrow(|story| story.as_ref().map(|story| std::ptr::from_ref(&story.story)));
+        round_trip(InkValue::DivertTarget("docks".to_string()));
+        let again = VALUE_STORY
+            .with_borrow(|story| story.as_ref().map(|story| std::ptr::from_ref(&story.story)));
+        assert!(story.is_some());
+        assert_eq!(story, again);
+    }
+
+    #[test]
+    fn test_values_serialize() {
+        let values = vec![
+            InkValue::DivertTarget("market".to_string()),
+            InkValue::VariablePointer {
+                name: "gold".to_string(),
+                context_index: 0,
+            },
+            InkValue::List(InkList::from_items([InkListItem::new(
+                "Inventory",
+                "key",
+                1,
+            )])),
+        ];
+        let json = serde_json::to_string(&values).unwrap();
+        let read: Vec<InkValue> = serde_json::from_str(&json).unwrap();
+        assert!(matches!(&read[0], InkValue::DivertTarget(path) if path == "market"));
+        assert!(matches!(&read[1], InkValue::VariablePointer { name, .. } if name == "gold"));
+        assert_eq!(read[2].get_list(), values[2].get_list());
     }
 }
@@ -59,17 +59,34 @@
 //!         .add_observer(handle_despawn_entity)
 //!         .run();
 //! }
+//! ```
+//!
+//! The events of bindings called while continuing a sequence are triggered in
+//! order, before the `DeliverLine` or `DeliverChoices` event of the content
+//! they were called from.
 
 mod bindings;
+mod blocking;
 mod choice;
 mod error;
+mod flow;
+mod globals;
+mod ink_list;
 mod ink_value;
 mod state;
 mod story;
+mod system_binding;
+mod validation;
 
 pub use bindings::*;
+pub use blocking::*;
 pub use choice::*;
 pub use error::*;
+pub use flow::*;
+pub use globals::*;
+pub use ink_list::*;
 pub use ink_value::*;
 pub use state::*;
 pub(crate) use story::*;
+pub use system_binding::*;
+pub(crate) use validation::*;
@@ -1,34 +1,215 @@
+use std::collections::BTreeMap;
+
 use bevy::{platform::collections::HashMap, reflect::Reflect};
 use bladeink::{story::Story, story_error::StoryError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ink::{InkFlows, InkValue},
+    resources::InkStoryVariables,
+};
 
-use crate::{ink::InkValue, prelude::InkVariables};
+/// The version of the format [`InkState`] is serialized with.
+pub const INK_STATE_FORMAT_VERSION: u32 = 1;
 
-#[derive(Debug, Clone, Reflect)]
+/// The saved state of a story, along with its tracked variables.
+///
+/// It serializes with an explicit format version, and the hash of the story
+/// JSON it was saved from. Loading it into another version of the story fails
+/// with [`InkStateError::StoryMismatch`], unless a migration is registered
+/// through [`AddInkStateMigrationApp`](crate::resources::AddInkStateMigrationApp).
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(into = "SerializedInkState", try_from = "SerializedInkState")]
 pub struct InkState {
     pub(crate) serialized_state: String,
     pub(crate) tracked_variables: HashMap<String, InkValue>,
+    pub(crate) flows: InkFlows,
+    pub(crate) story_hash: Option<u64>,
 }
 
 impl InkState {
     pub fn new(state: String, variables: HashMap<String, InkValue>) -> Self {
         InkState {
+            flows: InkFlows::from_serialized_state(&state),
             serialized_state: state,
             tracked_variables: variables,
+            story_hash: None,
         }
     }
+
+    /// The flows which were open when the state was saved. The position in
+    /// each flow is part of the saved state, and is restored when loading it.
+    pub fn flows(&self) -> &InkFlows {
+        &self.flows
+    }
+
+    /// The state of the story, as saved by the ink runtime.
+    pub fn serialized_state(&self) -> &str {
+        &self.serialized_state
+    }
+
+    /// Replaces the state of the story, e.g. while migrating an old save.
+    pub fn set_serialized_state(&mut self, state: String) {
+        self.flows = InkFlows::from_serialized_state(&state);
+        self.serialized_state = state;
+    }
+
+    /// The values of the variables tracked when the state was saved.
+    pub fn tracked_variables(&self) -> &HashMap<String, InkValue> {
+        &self.tracked_variables
+    }
+
+    pub fn tracked_variables_mut(&mut self) -> &mut HashMap<String, InkValue> {
+        &mut self.tracked_variables
+    }
+
+    /// The hash of the story JSON the state was saved from, if known.
+    pub fn story_hash(&self) -> Option<u64> {
+        self.story_hash
+    }
 }
 
 impl InkState {
-    pub fn from_story(story: &mut Story, variables: &InkVariables) -> Result<Self, StoryError> {
+    pub fn from_story(
+        story: &mut Story,
+        variables: InkStoryVariables<'_>,
+    ) -> Result<Self, StoryError> {
+        Self::from_story_tracking(story, variables.names())
+    }
+
+    /// Saves the state of the story, along with the current value of each of
+    /// the named variables.
+    pub(crate) fn from_story_tracking<'a>(
+        story: &mut Story,
+        names: impl IntoIterator<Item = &'a String>,
+    ) -> Result<Self, StoryError> {
         let state = story.save_state()?;
-        let mut variables = variables.clone();
+        let mut tracked_variables = HashMap::new();
 
-        for (name, value) in variables.tracked_variables.iter_mut() {
+        for name in names {
             if let Some(ink_value) = story.get_variable(name) {
-                *value = ink_value.clone().into();
+                tracked_variables.insert(name.clone(), ink_value.try_into()?);
             }
         }
 
-        Ok(Self::new(state, variables.tracked_variables))
+        Ok(Self::new(state, tracked_variables))
+    }
+}
+
+/// Hashes the JSON of a story, to tell the saves of different versions of it
+/// apart. Uses FNV-1a, which is stable across platforms and Rust versions.
+pub(crate) fn hash_story(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Error loading an [`InkState`].
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum InkStateError {
+    /// The state was serialized with a newer format.
+    #[error(
+        "Unsupported save format version {version}, the latest supported version is {INK_STATE_FORMAT_VERSION}"
+    )]
+    UnsupportedVersion { version: u32 },
+    /// The state was saved from another version of the story, and no
+    /// migration is registered for it.
+    #[error(
+        "The save was made with another version of the story ({saved:016x}, current {current:016x})"
+    )]
+    StoryMismatch { saved: u64, current: u64 },
+    /// The migration of the state failed.
+    #[error("Failed to migrate the save: {0}")]
+    Migration(String),
+    /// The ink runtime refused the state.
+    #[error("Failed to restore the state: {0}")]
+    Restore(String),
+}
+
+impl From<StoryError> for InkStateError {
+    fn from(err: StoryError) -> Self {
+        InkStateError::Restore(err.to_string())
+    }
+}
+
+/// The serialized form of an [`InkState`]. The flows are part of the state of
+/// the story, so they are read from it again.
+#[derive(Serialize, Deserialize)]
+struct SerializedInkState {
+    version: u32,
+    story_hash: Option<u64>,
+    state: String,
+    tracked_variables: BTreeMap<String, InkValue>,
+}
+
+impl From<InkState> for SerializedInkState {
+    fn from(state: InkState) -> Self {
+        Self {
+            version: INK_STATE_FORMAT_VERSION,
+            story_hash: state.story_hash,
+            state: state.serialized_state,
+            tracked_variables: state.tracked_variables.into_iter().collect(),
+        }
+    }
+}
+
+impl TryFrom<SerializedInkState> for InkState {
+    type Error = InkStateError;
+
+    fn try_from(serialized: SerializedInkState) -> Result<Self, Self::Error> {
+        if serialized.version > INK_STATE_FORMAT_VERSION {
+            return Err(InkStateError::UnsupportedVersion {
+                version: serialized.version,
+            });
+        }
+        let mut state = InkState::new(
+            serialized.state,
+            serialized.tracked_variables.into_iter().collect(),
+        );
+        state.story_hash = serialized.story_hash;
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> InkState {
+        let mut state = InkState::new(
+            r#"{"flows":{"DEFAULT_FLOW":{}},"currentFlowName":"DEFAULT_FLOW"}"#.to_string(),
+            HashMap::from_iter([("forceful".to_string(), InkValue::Int(2))]),
+        );
+        state.story_hash = Some(hash_story("{}"));
+        state
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let json = serde_json::to_value(state()).unwrap();
+        assert_eq!(json["version"], INK_STATE_FORMAT_VERSION);
+        assert_eq!(json["story_hash"], hash_story("{}"));
+
+        let read: InkState = serde_json::from_value(json).unwrap();
+        assert_eq!(read.serialized_state(), state().serialized_state());
+        assert_eq!(read.tracked_variables(), state().tracked_variables());
+        assert_eq!(read.story_hash(), Some(hash_story("{}")));
+        assert_eq!(read.flows(), state().flows());
+    }
+
+    #[test]
+    fn test_newer_format_is_refused() {
+        let mut json = serde_json::to_value(state()).unwrap();
+        json["version"] = (INK_STATE_FORMAT_VERSION + 1).into();
+        let err = serde_json::from_value::<InkState>(json).unwrap_err();
+        assert!(err.to_string().contains("Unsupported save format version"));
+    }
+
+    #[test]
+    fn test_story_hash_is_stable() {
+        assert_eq!(hash_story(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash_story("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash_story("{\"root\":1}"), hash_story("{\"root\":2}"));
     }
 }
@@ -1,48 +1,57 @@
 use bevy::prelude::*;
-use bladeink::story::Story;
+use bladeink::{story::Story, story_error::StoryError};
+use serde_json::{Value, json};
 
 use super::{InkBindingMap, InkErrorHandler};
 
+/// Parses the story and binds the given functions, then restores the existing
+/// state, if any, or seeds the story otherwise. Returns the story along with
+/// whether the state was restored, or why it couldn't be, in which case the
+/// story starts over.
 pub(crate) fn create_story(
     story_text: impl AsRef<str>,
     binding_defs: &InkBindingMap,
-    existing_state: &Option<String>,
-) -> Option<Story> {
-    let mut story = match Story::new(story_text.as_ref()) {
-        Ok(story) => story,
-        Err(err) => {
-            info!("Failed to parse story: {}", err);
-            return None;
-        }
-    };
+    existing_state: Option<&str>,
+    seed: Option<u64>,
+    allow_fallbacks: bool,
+) -> Result<(Story, Result<bool, StoryError>), StoryError> {
+    let mut story = Story::new(story_text.as_ref())?;
 
     story.set_error_handler(InkErrorHandler::boxed());
 
+    if allow_fallbacks
+        || binding_defs
+            .values()
+            .any(|binding_def| binding_def.allow_fallback)
+    {
+        story.set_allow_external_function_fallbacks(true);
+    }
+
     for (name, binding_def) in binding_defs.iter() {
-        match story.bind_external_function(name, binding_def.clone(), false) {
+        match story.bind_external_function(name, binding_def.create(), binding_def.lookahead_safe) {
             Ok(_) => {}
             Err(err) => {
-                info!("Failed to bind ink command '{name}': {err}");
+                error!("Failed to bind ink command '{name}': {err}");
             }
         }
     }
 
-    let should_init = if let Some(existing_state) = existing_state {
-        match story
+    let restored = match existing_state {
+        Some(existing_state) => story
             .reset_state()
             .and_then(|_| story.load_state(existing_state))
-        {
-            Ok(_) => false,
-            Err(err) => {
-                info!("Failed to load existing state: {err}");
-                true
-            }
-        }
-    } else {
-        true
+            .map(|_| true),
+        None => Ok(false),
     };
 
-    if should_init && story.can_continue() {
+    if !matches!(restored, Ok(true))
+        && let Some(seed) = seed
+        && let Err(err) = seed_story(&mut story, seed)
+    {
+        error!("Failed to seed story: {err}");
+    }
+
+    if !matches!(restored, Ok(true)) && story.can_continue() {
         match story.continue_maximally() {
             Ok(_) => {}
             Err(err) => {
@@ -51,5 +60,15 @@ pub(crate) fn create_story(
         }
     }
 
-    Some(story)
+    Ok((story, restored))
+}
+
+/// Sets the seed of a story which hasn't started yet. bladeink doesn't expose
+/// the seed, so it is set through the state of the story.
+fn seed_story(story: &mut Story, seed: u64) -> Result<(), StoryError> {
+    let mut state: Value = serde_json::from_str(&story.save_state()?)
+        .map_err(|err| StoryError::BadJson(err.to_string()))?;
+    // ink seeds are positive 32 bit integers
+    state["storySeed"] = json!(seed % (1 << 31));
+    story.load_state(&state.to_string())
 }
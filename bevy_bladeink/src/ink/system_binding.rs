@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bladeink::value_type::ValueType;
+
+use crate::ink::{InkBindingDefinition, InkValue};
+
+/// Values a system bound with
+/// [`bind_ink_system`](crate::ink::AddInkBindingApp::bind_ink_system) can
+/// return to the ink runtime.
+pub trait InkSystemOutput: 'static {
+    /// Converts the output into the value returned to ink, `None` if the
+    /// function returns nothing.
+    fn into_ink_value(self) -> Option<ValueType>;
+}
+
+impl InkSystemOutput for () {
+    fn into_ink_value(self) -> Option<ValueType> {
+        None
+    }
+}
+
+impl InkSystemOutput for bool {
+    fn into_ink_value(self) -> Option<ValueType> {
+        Some(self.into())
+    }
+}
+
+impl InkSystemOutput for i32 {
+    fn into_ink_value(self) -> Option<ValueType> {
+        Some(self.into())
+    }
+}
+
+impl InkSystemOutput for f32 {
+    fn into_ink_value(self) -> Option<ValueType> {
+        Some(self.into())
+    }
+}
+
+impl InkSystemOutput for String {
+    fn into_ink_value(self) -> Option<ValueType> {
+        Some(self.as_str().into())
+    }
+}
+
+impl InkSystemOutput for &'static str {
+    fn into_ink_value(self) -> Option<ValueType> {
+        Some(self.into())
+    }
+}
+
+impl InkSystemOutput for InkValue {
+    fn into_ink_value(self) -> Option<ValueType> {
+        self.to_value_type()
+    }
+}
+
+impl<T: InkSystemOutput> InkSystemOutput for Option<T> {
+    fn into_ink_value(self) -> Option<ValueType> {
+        self.and_then(InkSystemOutput::into_ink_value)
+    }
+}
+
+/// Parses the arguments of a system binding and runs it against the world.
+pub(crate) type InkSystemRunner =
+    Arc<dyn Fn(&str, &mut World, &[ValueType]) -> Option<ValueType> + Send + Sync>;
+
+/// Registers the system with the world, returning the runner invoking it.
+pub(crate) fn create_system_runner<E, Out, M>(
+    world: &mut World,
+    system: impl IntoSystem<In<E>, Out, M> + 'static,
+) -> InkSystemRunner
+where
+    E: InkBindingDefinition<Event = E>,
+    Out: InkSystemOutput,
+{
+    let id = world.register_system(system);
+    Arc::new(move |name, world, args| {
+        let event = match E::try_parse_event(args) {
+            Ok(event) => event,
+            Err(err) => {
+                error!("Failed to invoke ink binding '{name}': {err:?}");
+                return None;
+            }
+        };
+        match world.run_system_with(id, event) {
+            Ok(output) => output.into_ink_value(),
+            Err(err) => {
+                error!("Failed to invoke ink binding '{name}': {err}");
+                None
+            }
+        }
+    })
+}
+
+/// Marks that a system binding was registered, so commands lend the world to
+/// the story while it runs.
+#[cfg(not(feature = "threaded"))]
+#[derive(Resource, Default)]
+pub(crate) struct InkSystemBindings;
+
+#[cfg(not(feature = "threaded"))]
+mod local {
+    use std::{cell::RefCell, rc::Rc};
+
+    use bevy::prelude::*;
+    use bladeink::{story::external_functions::ExternalFunction, value_type::ValueType};
+
+    use super::InkSystemRunner;
+    use crate::{ink::call_once, runtime::InkEffects};
+
+    thread_local! {
+        static LENT_WORLD: RefCell<Option<World>> = const { RefCell::new(None) };
+        /// The world swapped in while the real one is lent, kept to be reused.
+        static SPARE_WORLD: RefCell<Option<World>> = const { RefCell::new(None) };
+        static LENT_OPS: RefCell<Option<InkEffects>> = const { RefCell::new(None) };
+    }
+
+    /// Moves the world out while `f` runs, so system bindings invoked by the
+    /// story can access it. Returns the ink operations queued by the bindings
+    /// meanwhile, to be run once the stories are back in the world.
+    pub(crate) fn lend_world<R>(world: &mut World, f: impl FnOnce() -> R) -> (R, InkEffects) {
+        let mut lent = SPARE_WORLD.take().unwrap_or_default();
+        std::mem::swap(world, &mut lent);
+        let previous_world = LENT_WORLD.replace(Some(lent));
+        let previous_ops = LENT_OPS.replace(Some(InkEffects::default()));
+        let result = f();
+        let ops = LENT_OPS.replace(previous_ops).unwrap_or_default();
+        let mut lent = LENT_WORLD
+            .replace(previous_world)
+            .expect("The lent world is returned by every system binding");
+        std::mem::swap(world, &mut lent);
+        SPARE_WORLD.set(Some(lent));
+        (result, ops)
+    }
+
+    /// Queues an ink operation issued while the world is lent, e.g. by a
+    /// command of a system binding. Returns `false` if the world isn't lent.
+    pub(crate) fn queue_lent_op(op: impl FnOnce(&mut World) + Send + 'static) -> bool {
+        LENT_OPS.with_borrow_mut(|ops| match ops {
+            Some(ops) => {
+                ops.push(op);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// External function running a system against the lent world.
+    pub(crate) struct InkSystemBindingFn {
+        runner: InkSystemRunner,
+    }
+
+    impl InkSystemBindingFn {
+        pub(crate) fn to_binding(runner: InkSystemRunner) -> Rc<RefCell<dyn ExternalFunction>> {
+            Rc::new(RefCell::new(Self { runner }))
+        }
+    }
+
+    impl ExternalFunction for InkSystemBindingFn {
+        fn call(&mut self, name: &str, args: Vec<ValueType>) -> Option<ValueType> {
+            call_once(|| {
+                let Some(mut world) = LENT_WORLD.take() else {
+                    error!("Failed to invoke ink binding '{name}': the world is not available");
+                    return None;
+                };
+                let result = (self.runner)(name, &mut world, &args);
+                LENT_WORLD.set(Some(world));
+                result
+            })
+        }
+    }
+}
+
+#[cfg(not(feature = "threaded"))]
+pub(crate) use local::*;
+
+#[cfg(feature = "threaded")]
+mod threaded {
+    use std::{cell::RefCell, rc::Rc};
+
+    use bevy::prelude::*;
+    use bladeink::{story::external_functions::ExternalFunction, value_type::ValueType};
+    use crossbeam_channel::Sender;
+
+    use super::InkSystemRunner;
+    use crate::ink::{InkValue, call_once};
+
+    /// A system binding invoked on the worker thread, waiting for the main
+    /// thread to run it.
+    pub(crate) struct InkSystemCall {
+        name: String,
+        runner: InkSystemRunner,
+        args: Vec<InkValue>,
+        reply: Sender<Option<InkValue>>,
+    }
+
+    impl InkSystemCall {
+        pub(crate) fn run(self, world: &mut World) {
+            let args: Option<Vec<ValueType>> =
+                self.args.iter().map(InkValue::to_value_type).collect();
+            let result = args
+                .and_then(|args| (self.runner)(&self.name, world, &args))
+                .and_then(|value| {
+                    InkValue::try_from(value)
+                        .inspect_err(|err| {
+                            error!(
+                                "Failed to read the value of ink binding '{}': {err}",
+                                self.name
+                            );
+                        })
+                        .ok()
+                });
+            // the worker stopped waiting if the runtime is shutting down
+            let _ = self.reply.send(result);
+        }
+    }
+
+    /// External function sending its calls to the main thread, blocking the
+    /// story until the system has run.
+    pub(crate) struct InkSystemBindingFn {
+        runner: InkSystemRunner,
+        calls: Sender<InkSystemCall>,
+    }
+
+    impl InkSystemBindingFn {
+        pub(crate) fn to_binding(
+            runner: InkSystemRunner,
+            calls: Sender<InkSystemCall>,
+        ) -> Rc<RefCell<dyn ExternalFunction>> {
+            Rc::new(RefCell::new(Self { runner, calls }))
+        }
+    }
+
+    impl ExternalFunction for InkSystemBindingFn {
+        fn call(&mut self, name: &str, args: Vec<ValueType>) -> Option<ValueType> {
+            call_once(|| {
+                let (reply, result) = crossbeam_channel::bounded(1);
+                let call = InkSystemCall {
+                    name: name.to_string(),
+                    runner: self.runner.clone(),
+                    args: InkValue::from_args(args)?,
+                    reply,
+                };
+                if self.calls.send(call).is_err() {
+                    error!("Failed to invoke ink binding '{name}': the world is not available");
+                    return None;
+                }
+                match result.recv() {
+                    Ok(value) => value.as_ref().and_then(InkValue::to_value_type),
+                    Err(_) => {
+                        error!("Failed to invoke ink binding '{name}': the world is not available");
+                        None
+                    }
+                }
+            })
+        }
+    }
+}
+
+#[cfg(feature = "threaded")]
+pub(crate) use threaded::*;
@@ -0,0 +1,118 @@
+use crate::{
+    assets::InkStoryMetadata,
+    events::{InkArityMismatch, InkBindingReport},
+    resources::InkStoryId,
+};
+
+use super::InkBindingMap;
+
+/// Compares the `EXTERNAL` functions called by the story with the bindings
+/// which apply to it. `allow_fallbacks` is the story level fallback option.
+pub(crate) fn validate_bindings(
+    story: InkStoryId,
+    metadata: &InkStoryMetadata,
+    bindings: &InkBindingMap,
+    allow_fallbacks: bool,
+) -> InkBindingReport {
+    let allow_fallbacks =
+        allow_fallbacks || bindings.values().any(|binding| binding.allow_fallback);
+
+    let mut report = InkBindingReport {
+        story,
+        ..Default::default()
+    };
+    for (name, args) in &metadata.externals {
+        match bindings.get(name) {
+            Some(binding) => {
+                if let Some(expected) = binding.arity
+                    && expected != *args
+                {
+                    report.arity_mismatches.push(InkArityMismatch {
+                        name: name.clone(),
+                        expected,
+                        called_with: *args,
+                    });
+                }
+            }
+            None if allow_fallbacks && metadata.knots.contains(name) => {}
+            None => report.missing.push(name.clone()),
+        }
+    }
+    report.unused = bindings
+        .keys()
+        .filter(|name| !metadata.externals.contains_key(*name))
+        .cloned()
+        .collect();
+
+    report.missing.sort();
+    report.unused.sort();
+    report.arity_mismatches.sort_by(|a, b| a.name.cmp(&b.name));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::platform::collections::HashMap;
+
+    use super::*;
+    use crate::ink::{InkBinding, create_closure_binding};
+
+    fn binding(arity: Option<usize>, allow_fallback: bool) -> InkBinding {
+        let mut binding = create_closure_binding(|_| None);
+        binding.arity = arity;
+        binding.allow_fallback = allow_fallback;
+        binding
+    }
+
+    fn metadata(externals: &[(&str, usize)], knots: &[&str]) -> InkStoryMetadata {
+        InkStoryMetadata {
+            externals: externals
+                .iter()
+                .map(|(name, args)| (name.to_string(), *args))
+                .collect::<HashMap<_, _>>(),
+            knots: knots.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_report_missing_unused_and_arity() {
+        let metadata = metadata(&[("play_sound", 1), ("buy", 2), ("roll", 0)], &[]);
+        let mut bindings = InkBindingMap::default();
+        bindings.insert("play_sound".to_string(), binding(Some(1), false));
+        bindings.insert("buy".to_string(), binding(Some(1), false));
+        bindings.insert("open_gate".to_string(), binding(None, false));
+
+        let report = validate_bindings(InkStoryId::DEFAULT, &metadata, &bindings, false);
+        assert_eq!(report.missing, vec!["roll"]);
+        assert_eq!(report.unused, vec!["open_gate"]);
+        assert_eq!(
+            report.arity_mismatches,
+            vec![InkArityMismatch {
+                name: "buy".to_string(),
+                expected: 1,
+                called_with: 2,
+            }]
+        );
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_fallback_functions_are_not_missing() {
+        let metadata = metadata(&[("roll", 0), ("open_gate", 0)], &["roll"]);
+        let mut bindings = InkBindingMap::default();
+        bindings.insert("open_gate".to_string(), binding(None, true));
+
+        let report = validate_bindings(InkStoryId::DEFAULT, &metadata, &bindings, false);
+        assert!(!report.has_errors());
+
+        // fallbacks are only enabled when a binding allows them
+        bindings.insert("open_gate".to_string(), binding(None, false));
+        let report = validate_bindings(InkStoryId::DEFAULT, &metadata, &bindings, false);
+        assert_eq!(report.missing, vec!["roll"]);
+
+        // or when the story itself allows them
+        let report = validate_bindings(InkStoryId::DEFAULT, &metadata, &bindings, true);
+        assert!(!report.has_errors());
+    }
+}
@@ -5,9 +5,15 @@
 //! Ink.Runtime library. As a result, it uses `Rc<RefCell<..>>` aggressively
 //! internally, which means Ink `Story`s are both `!Send` as well as `!Sync`.
 //!
-//! To get around this, we assume a single `Story` will be
-//! loaded for the entire application, stored in a `NonSend` resource, and all
-//! communication will occur through events and messages.
+//! To get around this, every loaded `Story` is stored in the `InkStories`
+//! `NonSend` resource, keyed by an `InkStoryId`, and all communication will
+//! occur through events and messages. Most applications only need the default
+//! story configured through the `InkStory` resource.
+//!
+//! With the `threaded` feature, `InkStories` lives on a dedicated worker thread
+//! instead, reached through the `Send + Sync` `InkRuntime` resource. Commands
+//! and events are unchanged, but events arrive once the worker has processed
+//! the command, rather than while the command is applied.
 //!
 //! ### Primary features & goals
 //! - Loading and managing .ink.json assets into `Story` resource
@@ -23,17 +29,18 @@
 //!   different storage mechanism.
 //!
 //! ### Non-goals
-//! - Allowing multiple `Story`s
 //! - Supporting a workflow based on bevy's Asset system.
 //! - Providing an out-of-the-box solution for UI
 
 use bevy::prelude::*;
 
-mod assets;
 mod components;
 mod plugin;
+mod runtime;
 mod systems;
 
+/// Assets holding ink stories, and their loaders.
+pub mod assets;
 /// Commands for instructing the Ink story to change state.
 pub mod commands;
 /// Events that can be emitted by the Ink story.
@@ -44,6 +51,8 @@ pub mod ink;
 pub mod prelude;
 /// Bevy resources for managing Ink stories and their associated data.
 pub mod resources;
+/// Save slots holding the state of Ink stories, with pluggable storage.
+pub mod save;
 
 #[cfg(feature = "ui")]
 /// A collection of UI components and systems for managing Ink dialogue, intended to be styled by the consumer.
@@ -5,8 +5,9 @@ use crate::{
     InkSystems,
     assets::{InkStoryJsonLoader, StoryJson},
     commands::{VariableUpdated, on_variable_updated},
-    ink::InkBindingMap,
-    resources::InkVariables,
+    ink::{InkBindingMap, InkStoryBindingMap},
+    resources::{InkAssetReady, InkBindingValidation, InkVariables},
+    runtime::{InkDeferredEffects, apply_deferred_effects},
     systems::*,
 };
 
@@ -16,12 +17,27 @@ pub struct InkPlugin;
 impl Plugin for InkPlugin {
     fn build(&self, app: &mut App) {
         app.add_crossbeam_event::<VariableUpdated>()
+            .add_crossbeam_event::<InkDeferredEffects>()
+            .add_observer(apply_deferred_effects)
             .init_resource::<InkVariables>()
+            .init_resource::<InkAssetReady>()
+            .init_resource::<InkBindingValidation>()
             .add_observer(on_variable_updated)
-            .add_observer(on_state_changed)
             .world_mut()
             .insert_non_send_resource(InkBindingMap::default());
 
+        app.world_mut()
+            .insert_non_send_resource(InkStoryBindingMap::default());
+        #[cfg(not(feature = "threaded"))]
+        app.world_mut()
+            .insert_non_send_resource(crate::resources::InkStories::default());
+        #[cfg(feature = "threaded")]
+        app.insert_resource(crate::resources::InkRuntime::spawn())
+            .add_systems(
+                PreUpdate,
+                crate::runtime::apply_ink_effects.in_set(InkSystems::HandleCommands),
+            );
+
         app.init_asset::<StoryJson>()
             .register_asset_loader(InkStoryJsonLoader);
 
@@ -30,10 +46,17 @@ impl Plugin for InkPlugin {
             (
                 load_ink_project,
                 process_story_json_events,
+                process_story_load_failures,
                 parse_story_asset,
             )
                 .chain()
                 .in_set(InkSystems::AssetHandling),
+        )
+        .add_systems(
+            Update,
+            push_variable_changes
+                .run_if(resource_changed::<InkVariables>)
+                .in_set(InkSystems::HandleCommands),
         );
     }
 }
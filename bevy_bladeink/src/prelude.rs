@@ -1,18 +1,35 @@
 pub use crate::{
+    assets::AddInkCompilerApp,
     commands::{
-        BeginSequenceCommandsExt, ContinueSequenceCommandsExt, LoadStateCommandsExt,
-        ResetStateCommandsExt, SelectChoiceCommandsExt, SetVariableCommandsExt,
-        TrackVariableCommandsExt,
+        BeginSequenceCommandsExt, ContinueSequenceCommandsExt, InkBindingCommandsExt,
+        InkPathCommandsExt, InkStoryCommandsExt, LoadStateCommandsExt, RemoveFlowCommandsExt,
+        ResetStateCommandsExt, ResumeCommandsExt, SelectChoiceCommandsExt, SetVariableCommandsExt,
+        SwitchFlowCommandsExt, TrackVariableCommandsExt,
     },
     components::InkPath,
     events::*,
-    ink::{AddInkBindingApp, InkBindingDefinition, InkBindingError},
+    ink::{
+        AddInkBindingApp, AddInkGlobalsApp, FromInkValue, InkBindingDefinition, InkBindingError,
+        InkGlobals, InkList, InkListItem, InkResumeToken, InkState, InkStateError, InkValue,
+        InkValueKind, InkVariableChange, ObserveInkVariableApp,
+    },
     plugin::InkPlugin,
-    resources::{InkStory, InkVariables},
+    resources::{
+        AddInkStateMigrationApp, AddInkStoryApp, InkBindingValidation, InkLoadProgress, InkStory,
+        InkStoryId, InkStoryRegistry, InkVariables,
+    },
+    save::{
+        InkAutosave, InkAutosavePolicy, InkFileStorage, InkMemoryStorage, InkPlaythrough,
+        InkSaveCommandsExt, InkSaveError, InkSavePlugin, InkSaveStorage, InkSaves, InkSlotDeleted,
+        InkSlotFailed, InkSlotLoaded, InkSlotMetadata, InkSlotSaved,
+    },
 };
 
+#[cfg(feature = "threaded")]
+pub use crate::resources::InkRuntime;
+
 // Re-export the derive macro
-pub use bevy_bladeink_derive::InkBinding;
+pub use bevy_bladeink_derive::{InkBinding, InkGlobals};
 
 #[cfg(feature = "ui")]
 pub use crate::ui::prelude::*;
@@ -1,6 +1,7 @@
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
 
-use crate::assets::StoryJson;
+use crate::{assets::StoryJson, resources::InkStoryId};
 
-#[derive(Resource)]
-pub struct InkAssetReady(pub Handle<StoryJson>);
+/// Story assets which finished loading, waiting to be parsed into a `Story`.
+#[derive(Resource, Default)]
+pub struct InkAssetReady(pub HashMap<InkStoryId, Handle<StoryJson>>);
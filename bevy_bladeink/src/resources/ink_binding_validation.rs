@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+/// How the bindings of a story are validated when it is loaded, see
+/// [`InkBindingReport`](crate::events::InkBindingReport).
+#[derive(Resource, Default, Clone, Debug)]
+pub struct InkBindingValidation {
+    /// Refuses to start a story which calls functions it can't run, logging
+    /// the report as an error. A reloaded story keeps running the previous
+    /// version instead.
+    pub strict: bool,
+}
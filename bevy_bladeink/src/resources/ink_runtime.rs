@@ -0,0 +1,78 @@
+use std::thread;
+
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{
+    ink::InkSystemCall,
+    resources::InkStories,
+    runtime::{InkEffects, InkOp},
+};
+
+/// The `Send + Sync` handle to the worker thread which owns every ink `Story`
+/// when the `threaded` feature is enabled. Commands are sent to the worker,
+/// and the events they produce are triggered once it has finished with them,
+/// so long running commands don't stall frames. System bindings block the
+/// story until the main thread has run them in `PreUpdate`.
+#[derive(Resource)]
+pub struct InkRuntime {
+    ops: Sender<InkOp>,
+    effects: Receiver<InkEffects>,
+    calls: (Sender<InkSystemCall>, Receiver<InkSystemCall>),
+}
+
+impl InkRuntime {
+    /// Spawns the worker thread. It shuts down once the runtime is dropped.
+    pub(crate) fn spawn() -> Self {
+        let (ops, op_receiver) = crossbeam_channel::unbounded::<InkOp>();
+        let (effect_sender, effects) = crossbeam_channel::unbounded();
+
+        thread::Builder::new()
+            .name("bevy_bladeink".to_string())
+            .spawn(move || {
+                let mut stories = InkStories::default();
+                for op in op_receiver {
+                    if effect_sender.send(op(&mut stories)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to spawn the ink worker thread");
+
+        Self {
+            ops,
+            effects,
+            calls: crossbeam_channel::unbounded(),
+        }
+    }
+
+    /// Runs the function against the loaded stories on the worker thread.
+    pub fn run(&self, f: impl FnOnce(&mut InkStories) + Send + 'static) {
+        self.send(Box::new(move |stories| {
+            f(stories);
+            InkEffects::default()
+        }));
+    }
+
+    pub(crate) fn send(&self, op: InkOp) {
+        if self.ops.send(op).is_err() {
+            error!("Failed to send ink command: the ink worker thread has stopped");
+        }
+    }
+
+    /// The channel system bindings send their calls through, to be run on the
+    /// main thread.
+    pub(crate) fn system_calls(&self) -> Sender<InkSystemCall> {
+        self.calls.0.clone()
+    }
+
+    /// Drains the system binding calls the worker is waiting on.
+    pub(crate) fn pending_calls(&self) -> Vec<InkSystemCall> {
+        self.calls.1.try_iter().collect()
+    }
+
+    /// Drains the effects of every operation the worker has finished.
+    pub(crate) fn finished(&self) -> Vec<InkEffects> {
+        self.effects.try_iter().collect()
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    ink::{InkState, InkStateError},
+    resources::InkStoryId,
+};
+
+/// Transforms a state saved from another version of a story, so it can be
+/// loaded into the current one. [`InkState::story_hash`] tells which version
+/// of the story the state was saved from.
+pub type InkStateMigration = Arc<dyn Fn(&mut InkState) -> Result<(), InkStateError> + Send + Sync>;
+
+/// The migrations of the states of each story, registered through
+/// [`AddInkStateMigrationApp`].
+#[derive(Resource, Default, Clone)]
+pub struct InkStateMigrations {
+    migrations: HashMap<InkStoryId, InkStateMigration>,
+}
+
+impl InkStateMigrations {
+    pub fn get(&self, id: InkStoryId) -> Option<&InkStateMigration> {
+        self.migrations.get(&id)
+    }
+}
+
+/// Allows loading states saved from previous versions of a story.
+pub trait AddInkStateMigrationApp {
+    /// Migrates the states of the default story saved from another version of
+    /// it, replacing any previous migration.
+    fn add_ink_state_migration(
+        &mut self,
+        migration: impl Fn(&mut InkState) -> Result<(), InkStateError> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Migrates the states of a single story saved from another version of it.
+    fn add_ink_state_migration_for(
+        &mut self,
+        story: InkStoryId,
+        migration: impl Fn(&mut InkState) -> Result<(), InkStateError> + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl AddInkStateMigrationApp for App {
+    fn add_ink_state_migration(
+        &mut self,
+        migration: impl Fn(&mut InkState) -> Result<(), InkStateError> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.add_ink_state_migration_for(InkStoryId::DEFAULT, migration)
+    }
+
+    fn add_ink_state_migration_for(
+        &mut self,
+        story: InkStoryId,
+        migration: impl Fn(&mut InkState) -> Result<(), InkStateError> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<InkStateMigrations>()
+            .migrations
+            .insert(story, Arc::new(migration));
+        self
+    }
+}
@@ -0,0 +1,412 @@
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use bladeink::{
+    story::{Story, variable_observer::VariableObserver},
+    story_error::StoryError,
+    value_type::ValueType,
+};
+
+use crate::{
+    ink::{
+        InkBinding, InkBindingMap, InkFlows, InkListDefinitions, InkResumeToken, InkState,
+        InkSuspension, InkValue,
+    },
+    resources::{InkStory, InkStoryId},
+};
+
+/// Every parsed ink `Story`, keyed by [`InkStoryId`]. Stored as a `NonSend`
+/// resource, since `Story` is neither `Send` nor `Sync`. With the `threaded`
+/// feature, it is owned by the ink worker thread instead, and reached through
+/// [`InkRuntime::run`](crate::resources::InkRuntime::run).
+#[derive(Default)]
+pub struct InkStories {
+    stories: HashMap<InkStoryId, Story>,
+    flows: HashMap<InkStoryId, InkFlows>,
+    speakers: HashMap<InkStoryId, HashMap<Option<String>, Entity>>,
+    tracked: HashMap<InkStoryId, HashSet<String>>,
+    /// The observer forwarding the changes of the tracked variables of each
+    /// story, kept to stop observing them.
+    variable_observers: HashMap<InkStoryId, Rc<RefCell<dyn VariableObserver>>>,
+    bound: HashMap<InkStoryId, InkBoundFunctions>,
+    /// The hash of the JSON each story was created from.
+    hashes: HashMap<InkStoryId, u64>,
+    lists: HashMap<InkStoryId, InkListDefinitions>,
+    suspensions: HashMap<InkStoryId, InkSuspension>,
+    next_token: u64,
+    /// Whether anything observes [`InkStateUpdate`](crate::events::InkStateUpdate),
+    /// as of the operation being run.
+    publish_states: bool,
+}
+
+impl InkStories {
+    /// Returns the story with the given id, if it has finished loading.
+    pub fn get(&self, id: InkStoryId) -> Option<&Story> {
+        self.stories.get(&id)
+    }
+
+    /// Returns the story with the given id, if it has finished loading.
+    pub fn get_mut(&mut self, id: InkStoryId) -> Option<&mut Story> {
+        self.stories.get_mut(&id)
+    }
+
+    /// Returns `true` if the story with the given id has finished loading.
+    pub fn contains(&self, id: InkStoryId) -> bool {
+        self.stories.contains_key(&id)
+    }
+
+    /// Iterates the ids of every loaded story.
+    pub fn ids(&self) -> impl Iterator<Item = InkStoryId> + '_ {
+        self.stories.keys().copied()
+    }
+
+    /// Returns the flows open in the story with the given id.
+    pub fn flows(&self, id: InkStoryId) -> Option<&InkFlows> {
+        self.flows.get(&id)
+    }
+
+    /// Returns the flow the story is running, `None` for the default flow.
+    pub fn current_flow(&self, id: InkStoryId) -> Option<&str> {
+        self.flows(id).and_then(InkFlows::current)
+    }
+
+    /// Returns the entity whose [`InkPath`](crate::components::InkPath)
+    /// started the sequence running in the given flow, if any.
+    pub fn speaker(&self, id: InkStoryId, flow: Option<&str>) -> Option<Entity> {
+        self.speakers
+            .get(&id)
+            .and_then(|speakers| speakers.get(&flow.map(str::to_string)))
+            .copied()
+    }
+
+    /// Returns `true` if the states of the stories are published as they
+    /// change.
+    pub(crate) fn publishes_states(&self) -> bool {
+        self.publish_states
+    }
+
+    pub(crate) fn set_publish_states(&mut self, publish: bool) {
+        self.publish_states = publish;
+    }
+
+    /// Returns `true` if the story is waiting on a blocking binding.
+    pub fn is_suspended(&self, id: InkStoryId) -> bool {
+        self.suspensions.contains_key(&id)
+    }
+
+    /// Returns `true` if any blocking binding is bound to the story.
+    pub(crate) fn has_blocking_bindings(&self, id: InkStoryId) -> bool {
+        self.bound
+            .get(&id)
+            .is_some_and(|bound| !bound.blocking.is_empty())
+    }
+
+    /// Binds the function to a loaded story, replacing its current binding.
+    pub(crate) fn bind(&mut self, id: InkStoryId, name: &str, binding: &InkBinding) {
+        let Some(story) = self.stories.get_mut(&id) else {
+            return;
+        };
+        // the function may not be bound yet
+        let _ = story.unbind_external_function(name);
+        let bound = self.bound.entry(id).or_default();
+        bound.remove(name);
+        if let Err(err) =
+            story.bind_external_function(name, binding.create(), binding.lookahead_safe)
+        {
+            error!("Failed to bind ink function '{name}': {err}");
+        } else {
+            bound.insert(name, binding);
+        }
+        story.set_allow_external_function_fallbacks(bound.allows_fallbacks());
+    }
+
+    /// Removes the binding of the function from a loaded story.
+    pub(crate) fn unbind(&mut self, id: InkStoryId, name: &str) {
+        let Some(story) = self.stories.get_mut(&id) else {
+            return;
+        };
+        // the function may never have been bound
+        let _ = story.unbind_external_function(name);
+        if let Some(bound) = self.bound.get_mut(&id) {
+            bound.remove(name);
+            story.set_allow_external_function_fallbacks(bound.allows_fallbacks());
+        }
+    }
+
+    pub(crate) fn suspension(&self, id: InkStoryId) -> Option<&InkSuspension> {
+        self.suspensions.get(&id)
+    }
+
+    pub(crate) fn suspension_mut(&mut self, id: InkStoryId) -> Option<&mut InkSuspension> {
+        self.suspensions.get_mut(&id)
+    }
+
+    /// Suspends the story on the given function, returning the token to resume
+    /// it with.
+    pub(crate) fn suspend(
+        &mut self,
+        id: InkStoryId,
+        flow: Option<String>,
+        function: String,
+        resolved: Vec<Option<InkValue>>,
+        replayed: Vec<Option<ValueType>>,
+    ) -> InkResumeToken {
+        self.next_token += 1;
+        let token = InkResumeToken {
+            story: id,
+            id: self.next_token,
+        };
+        self.suspensions.insert(
+            id,
+            InkSuspension {
+                token,
+                flow,
+                function,
+                resolved,
+                replayed,
+                resumed: false,
+            },
+        );
+        token
+    }
+
+    pub(crate) fn clear_suspension(&mut self, id: InkStoryId) {
+        self.suspensions.remove(&id);
+    }
+
+    pub(crate) fn set_speaker(
+        &mut self,
+        id: InkStoryId,
+        flow: Option<String>,
+        speaker: Option<Entity>,
+    ) {
+        let speakers = self.speakers.entry(id).or_default();
+        match speaker {
+            Some(entity) => speakers.insert(flow, entity),
+            None => speakers.remove(&flow),
+        };
+    }
+
+    pub(crate) fn clear_speakers(&mut self, id: InkStoryId) {
+        self.speakers.remove(&id);
+    }
+
+    /// The names of the variables tracked in the story.
+    pub(crate) fn tracked(&self, id: InkStoryId) -> Vec<String> {
+        self.tracked
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn track(&mut self, id: InkStoryId, name: String) {
+        self.tracked.entry(id).or_default().insert(name);
+    }
+
+    /// Stops observing a tracked variable. Returns whether it was tracked.
+    pub(crate) fn untrack(&mut self, id: InkStoryId, name: &str) -> Result<bool, StoryError> {
+        if !self
+            .tracked
+            .get_mut(&id)
+            .is_some_and(|tracked| tracked.remove(name))
+        {
+            return Ok(false);
+        }
+        if let (Some(story), Some(observer)) =
+            (self.stories.get_mut(&id), self.variable_observers.get(&id))
+        {
+            story.remove_variable_observer(observer, Some(name))?;
+        }
+        Ok(true)
+    }
+
+    /// Returns the observer of the tracked variables of the story, creating it
+    /// if necessary.
+    pub(crate) fn variable_observer(
+        &mut self,
+        id: InkStoryId,
+        create: impl FnOnce() -> Rc<RefCell<dyn VariableObserver>>,
+    ) -> Rc<RefCell<dyn VariableObserver>> {
+        self.variable_observers
+            .entry(id)
+            .or_insert_with(create)
+            .clone()
+    }
+
+    /// Saves the state of the story, along with the variables tracked in it.
+    pub(crate) fn save_state(&mut self, id: InkStoryId) -> Option<Result<InkState, StoryError>> {
+        let story = self.stories.get_mut(&id)?;
+        let tracked = self.tracked.get(&id).into_iter().flatten();
+        Some(
+            InkState::from_story_tracking(story, tracked).map(|mut state| {
+                state.story_hash = self.hashes.get(&id).copied();
+                state
+            }),
+        )
+    }
+
+    /// The hash of the JSON the story was created from.
+    pub(crate) fn story_hash(&self, id: InkStoryId) -> Option<u64> {
+        self.hashes.get(&id).copied()
+    }
+
+    /// The `LIST`s declared by the story, if it is loaded.
+    pub(crate) fn list_definitions(&self, id: InkStoryId) -> Option<&InkListDefinitions> {
+        self.lists.get(&id)
+    }
+
+    pub(crate) fn get_with_flows_mut(
+        &mut self,
+        id: InkStoryId,
+    ) -> Option<(&mut Story, &mut InkFlows)> {
+        let story = self.stories.get_mut(&id)?;
+        let flows = self.flows.entry(id).or_default();
+        Some((story, flows))
+    }
+
+    /// Stores the story, along with the functions bound to it, the hash of its
+    /// JSON, and the `LIST`s it declares.
+    pub(crate) fn insert(
+        &mut self,
+        id: InkStoryId,
+        story: Story,
+        bound: InkBoundFunctions,
+        hash: u64,
+        lists: InkListDefinitions,
+    ) -> Option<Story> {
+        self.flows.insert(id, InkFlows::from_story(&story));
+        self.bound.insert(id, bound);
+        self.hashes.insert(id, hash);
+        self.lists.insert(id, lists);
+        self.stories.insert(id, story)
+    }
+}
+
+/// The functions bound to a story which change how it runs.
+#[derive(Default)]
+pub(crate) struct InkBoundFunctions {
+    /// The functions which suspend the story.
+    blocking: HashSet<String>,
+    /// The functions which fall back to a knot of the same name when unbound.
+    fallbacks: HashSet<String>,
+    /// Whether the story always allows external function fallbacks.
+    allow_fallbacks: bool,
+}
+
+impl InkBoundFunctions {
+    pub(crate) fn new(bindings: &InkBindingMap, allow_fallbacks: bool) -> Self {
+        let mut bound = Self {
+            allow_fallbacks,
+            ..default()
+        };
+        for (name, binding) in bindings {
+            bound.insert(name, binding);
+        }
+        bound
+    }
+
+    fn insert(&mut self, name: &str, binding: &InkBinding) {
+        if binding.blocking {
+            self.blocking.insert(name.to_string());
+        }
+        if binding.allow_fallback {
+            self.fallbacks.insert(name.to_string());
+        }
+    }
+
+    fn remove(&mut self, name: &str) {
+        self.blocking.remove(name);
+        self.fallbacks.remove(name);
+    }
+
+    /// Returns `true` if the story should fall back to knots for unbound
+    /// external functions.
+    pub(crate) fn allows_fallbacks(&self) -> bool {
+        self.allow_fallbacks || !self.fallbacks.is_empty()
+    }
+}
+
+/// Stories loaded alongside the default [`InkStory`] resource, keyed by their
+/// [`InkStoryId`].
+#[derive(Resource, Debug, Clone, Default)]
+pub struct InkStoryRegistry {
+    stories: HashMap<InkStoryId, InkStory>,
+}
+
+impl InkStoryRegistry {
+    /// Registers a story to be loaded under the given id. Registering a story
+    /// under [`InkStoryId::DEFAULT`] is ignored, use the [`InkStory`] resource
+    /// instead.
+    pub fn insert(&mut self, id: InkStoryId, story: InkStory) {
+        if id == InkStoryId::DEFAULT {
+            warn!(
+                "Ignoring story registered with the default id, insert the InkStory resource instead."
+            );
+            return;
+        }
+        self.stories.insert(id, story);
+    }
+
+    /// Returns the story registered under the given id.
+    pub fn get(&self, id: InkStoryId) -> Option<&InkStory> {
+        self.stories.get(&id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: InkStoryId) -> Option<&mut InkStory> {
+        self.stories.get_mut(&id)
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (InkStoryId, &mut InkStory)> {
+        self.stories.iter_mut().map(|(id, story)| (*id, story))
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (InkStoryId, &InkStory)> {
+        self.stories.iter().map(|(id, story)| (*id, story))
+    }
+}
+
+/// Allows registering additional stories with the application.
+pub trait AddInkStoryApp {
+    /// Loads an additional story, addressable through the given id.
+    fn add_ink_story(&mut self, id: InkStoryId, story: InkStory) -> &mut Self;
+}
+
+impl AddInkStoryApp for App {
+    fn add_ink_story(&mut self, id: InkStoryId, story: InkStory) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<InkStoryRegistry>()
+            .insert(id, story);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(allow_fallback: bool) -> InkBinding {
+        let mut binding = crate::ink::create_closure_binding(|_| None);
+        binding.allow_fallback = allow_fallback;
+        binding
+    }
+
+    #[test]
+    fn test_fallbacks_follow_bindings() {
+        let bindings = InkBindingMap::from([("plain".to_string(), binding(false))]);
+        let mut bound = InkBoundFunctions::new(&bindings, false);
+        assert!(!bound.allows_fallbacks());
+
+        bound.insert("optional", &binding(true));
+        assert!(bound.allows_fallbacks());
+        bound.remove("optional");
+        assert!(!bound.allows_fallbacks());
+
+        let bound = InkBoundFunctions::new(&bindings, true);
+        assert!(bound.allows_fallbacks());
+    }
+}
@@ -1,14 +1,41 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::assets::StoryJson;
+use crate::{assets::StoryJson, events::InkLoadStage, ink::InkState};
+
+/// Identifies a loaded ink story. The story inserted through the [`InkStory`]
+/// resource is always [`InkStoryId::DEFAULT`]; additional stories are
+/// registered with their own id through
+/// [`InkStoryRegistry`](crate::resources::InkStoryRegistry).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect, Serialize, Deserialize)]
+pub struct InkStoryId(pub u32);
+
+impl InkStoryId {
+    /// The id of the story configured through the [`InkStory`] resource.
+    pub const DEFAULT: InkStoryId = InkStoryId(0);
+}
 
 /// Represents an ink project resource. Insert to kick off the systems that
 /// manage the ink project.
+///
+/// The options of the story are applied when it is created:
+///
+/// ```rust,ignore
+/// commands.insert_resource(
+///     InkStory::new("story.ink.json")
+///         .with_state(saved_state)
+///         .with_seed(42)
+///         .allow_external_fallbacks(true),
+/// );
+/// ```
 #[derive(Resource, Debug, Clone)]
 pub struct InkStory {
     asset_path: String,
     handle: Option<Handle<StoryJson>>,
-    state: Option<String>,
+    state: Option<InkState>,
+    seed: Option<u64>,
+    allow_external_fallbacks: bool,
+    progress: InkLoadProgress,
 }
 
 impl InkStory {
@@ -18,8 +45,41 @@ impl InkStory {
             asset_path: asset_path.as_ref().to_string(),
             handle: None,
             state: None,
+            seed: None,
+            allow_external_fallbacks: false,
+            progress: InkLoadProgress::default(),
         }
     }
+
+    /// Starts the story from a saved state, restoring the variables it tracked
+    /// into [`InkVariables`](crate::resources::InkVariables). A state saved
+    /// from another version of the story is migrated first, see
+    /// [`AddInkStateMigrationApp`](crate::resources::AddInkStateMigrationApp).
+    pub fn with_state(mut self, state: InkState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Seeds the randomness of the story, e.g. `RANDOM` and shuffles, to make
+    /// it deterministic. Ink seeds are 31 bits, so larger seeds are reduced.
+    /// Stories started from a state keep the seed saved with it.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Lets the story run the ink fallback of external functions which aren't
+    /// bound, instead of failing. Bindings declared with `allow_fallback`
+    /// enable it regardless.
+    pub fn allow_external_fallbacks(mut self, allow: bool) -> Self {
+        self.allow_external_fallbacks = allow;
+        self
+    }
+
+    /// How far along loading the story is, e.g. for loading screens.
+    pub fn load_progress(&self) -> &InkLoadProgress {
+        &self.progress
+    }
 }
 
 impl InkStory {
@@ -35,12 +95,61 @@ impl InkStory {
         self.handle = Some(handle);
     }
 
-    pub(crate) fn state(&self) -> &Option<String> {
-        &self.state
+    pub(crate) fn state(&self) -> Option<&InkState> {
+        self.state.as_ref()
+    }
+
+    pub(crate) fn seed(&self) -> Option<u64> {
+        self.seed
     }
 
-    #[expect(dead_code, reason = "TODO")]
-    pub(crate) fn set_state(&mut self, state: impl AsRef<str>) {
-        self.state = Some(state.as_ref().to_string());
+    pub(crate) fn allows_external_fallbacks(&self) -> bool {
+        self.allow_external_fallbacks
+    }
+
+    pub(crate) fn set_load_progress(&mut self, progress: InkLoadProgress) {
+        self.progress = progress;
+    }
+}
+
+/// The steps of loading a story, reported by [`InkStory::load_progress`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InkLoadProgress {
+    /// The asset has not been requested yet.
+    #[default]
+    NotStarted,
+    /// The asset is being read, and compiled for `.ink` files.
+    LoadingAsset,
+    /// The asset is loaded, and the story is being created from it.
+    CreatingStory,
+    /// The story is ready to receive commands.
+    Ready,
+    /// The story failed to load, see
+    /// [`StoryLoadFailed`](crate::events::StoryLoadFailed).
+    Failed { stage: InkLoadStage, error: String },
+}
+
+impl InkLoadProgress {
+    /// The share of the loading done, from `0.0` to `1.0`. Failed loads
+    /// report `0.0`.
+    pub fn fraction(&self) -> f32 {
+        match self {
+            InkLoadProgress::NotStarted | InkLoadProgress::Failed { .. } => 0.0,
+            InkLoadProgress::LoadingAsset => 0.25,
+            InkLoadProgress::CreatingStory => 0.75,
+            InkLoadProgress::Ready => 1.0,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        matches!(self, InkLoadProgress::Ready)
+    }
+
+    /// The reason the story failed to load, if it did.
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            InkLoadProgress::Failed { error, .. } => Some(error),
+            _ => None,
+        }
     }
 }
@@ -1,34 +1,180 @@
 use bevy::{platform::collections::HashMap, prelude::*};
 
-use crate::ink::InkValue;
+use crate::{
+    ink::{InkList, InkValue},
+    resources::InkStoryId,
+};
 
+/// Tracked ink variables for every loaded story. The accessors on this type
+/// read from the default story, use [`InkVariables::story`] for the others.
+///
+/// Tracked variables changed through [`InkVariables::set`] are written back to
+/// their story.
 #[derive(Resource, Default, Clone)]
 pub struct InkVariables {
-    pub(crate) tracked_variables: HashMap<String, InkValue>,
+    pub(crate) stories: HashMap<InkStoryId, HashMap<String, InkValue>>,
+    /// The values the stories last reported, to tell the variables changed in
+    /// Bevy apart from the ones changed by the stories.
+    synced: HashMap<InkStoryId, HashMap<String, InkValue>>,
 }
 
 impl InkVariables {
+    /// Returns a view over the variables tracked for the given story.
+    pub fn story(&self, id: InkStoryId) -> InkStoryVariables<'_> {
+        InkStoryVariables {
+            tracked_variables: self.stories.get(&id),
+        }
+    }
+
     pub fn get_string(&self, variable_name: &str) -> Option<&String> {
-        self.tracked_variables
-            .get(variable_name)
-            .and_then(|value| value.get_string())
+        self.story(InkStoryId::DEFAULT).get_string(variable_name)
     }
 
     pub fn get_bool(&self, variable_name: &str) -> Option<bool> {
-        self.tracked_variables
-            .get(variable_name)
-            .and_then(InkValue::get_bool)
+        self.story(InkStoryId::DEFAULT).get_bool(variable_name)
     }
 
     pub fn get_int(&self, variable_name: &str) -> Option<i32> {
-        self.tracked_variables
-            .get(variable_name)
-            .and_then(InkValue::get_int)
+        self.story(InkStoryId::DEFAULT).get_int(variable_name)
     }
 
     pub fn get_float(&self, variable_name: &str) -> Option<f32> {
+        self.story(InkStoryId::DEFAULT).get_float(variable_name)
+    }
+
+    pub fn get_list(&self, variable_name: &str) -> Option<&InkList> {
+        self.story(InkStoryId::DEFAULT).get_list(variable_name)
+    }
+
+    pub fn get_divert_target(&self, variable_name: &str) -> Option<&String> {
+        self.story(InkStoryId::DEFAULT)
+            .get_divert_target(variable_name)
+    }
+
+    /// Sets a tracked variable of the default story, which is then written
+    /// back to the story. Returns `false` if the variable isn't tracked.
+    pub fn set(&mut self, variable_name: &str, value: impl Into<InkValue>) -> bool {
+        self.set_for(InkStoryId::DEFAULT, variable_name, value)
+    }
+
+    /// Sets a tracked variable of the given story, which is then written back
+    /// to the story. Returns `false` if the variable isn't tracked.
+    pub fn set_for(
+        &mut self,
+        id: InkStoryId,
+        variable_name: &str,
+        value: impl Into<InkValue>,
+    ) -> bool {
+        let Some(tracked) = self
+            .stories
+            .get_mut(&id)
+            .and_then(|variables| variables.get_mut(variable_name))
+        else {
+            return false;
+        };
+        *tracked = value.into();
+        true
+    }
+
+    /// Records the value of a variable reported by its story.
+    pub(crate) fn update_from_story(&mut self, id: InkStoryId, name: String, value: InkValue) {
+        self.synced
+            .entry(id)
+            .or_default()
+            .insert(name.clone(), value.clone());
+        self.stories.entry(id).or_default().insert(name, value);
+    }
+
+    /// Removes a variable which is no longer tracked.
+    pub(crate) fn forget(&mut self, id: InkStoryId, name: &str) {
+        for variables in [&mut self.stories, &mut self.synced] {
+            if let Some(variables) = variables.get_mut(&id) {
+                variables.remove(name);
+            }
+        }
+    }
+
+    /// Returns the variables changed in Bevy since their story last reported
+    /// them, considering them reported from now on.
+    pub(crate) fn take_changes(&mut self) -> Vec<(InkStoryId, String, InkValue)> {
+        let mut changes = Vec::new();
+        for (id, variables) in &self.stories {
+            let synced = self.synced.entry(*id).or_default();
+            for (name, value) in variables {
+                if synced.get(name) != Some(value) {
+                    synced.insert(name.clone(), value.clone());
+                    changes.push((*id, name.clone(), value.clone()));
+                }
+            }
+        }
+        changes
+    }
+}
+
+/// The variables tracked for a single story.
+#[derive(Clone, Copy)]
+pub struct InkStoryVariables<'a> {
+    tracked_variables: Option<&'a HashMap<String, InkValue>>,
+}
+
+impl<'a> InkStoryVariables<'a> {
+    /// Returns the value of a tracked variable, whatever its type.
+    pub fn get(&self, variable_name: &str) -> Option<&'a InkValue> {
         self.tracked_variables
-            .get(variable_name)
-            .and_then(InkValue::get_float)
+            .and_then(|variables| variables.get(variable_name))
+    }
+
+    pub fn get_string(&self, variable_name: &str) -> Option<&'a String> {
+        self.get(variable_name).and_then(InkValue::get_string)
+    }
+
+    pub fn get_bool(&self, variable_name: &str) -> Option<bool> {
+        self.get(variable_name).and_then(InkValue::get_bool)
+    }
+
+    pub fn get_int(&self, variable_name: &str) -> Option<i32> {
+        self.get(variable_name).and_then(InkValue::get_int)
+    }
+
+    pub fn get_float(&self, variable_name: &str) -> Option<f32> {
+        self.get(variable_name).and_then(InkValue::get_float)
+    }
+
+    pub fn get_list(&self, variable_name: &str) -> Option<&'a InkList> {
+        self.get(variable_name).and_then(InkValue::get_list)
+    }
+
+    pub fn get_divert_target(&self, variable_name: &str) -> Option<&'a String> {
+        self.get(variable_name)
+            .and_then(InkValue::get_divert_target)
+    }
+
+    pub(crate) fn names(&self) -> impl Iterator<Item = &'a String> + use<'a> {
+        self.tracked_variables.into_iter().flat_map(HashMap::keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_changes_made_in_bevy_are_taken() {
+        let mut variables = InkVariables::default();
+        variables.update_from_story(InkStoryId::DEFAULT, "gold".to_string(), 1.into());
+        assert!(variables.take_changes().is_empty());
+
+        assert!(variables.set("gold", 5));
+        assert!(!variables.set("silver", 5));
+        assert_eq!(
+            variables.take_changes(),
+            vec![(InkStoryId::DEFAULT, "gold".to_string(), InkValue::Int(5))]
+        );
+        assert!(variables.take_changes().is_empty());
+
+        // the story echoing the change back doesn't count as a change
+        variables.update_from_story(InkStoryId::DEFAULT, "gold".to_string(), 5.into());
+        assert!(variables.take_changes().is_empty());
+        assert_eq!(variables.get_int("gold"), Some(5));
     }
 }
@@ -1,7 +1,17 @@
 mod ink_asset_ready;
+mod ink_binding_validation;
+#[cfg(feature = "threaded")]
+mod ink_runtime;
+mod ink_state_migrations;
+mod ink_stories;
 mod ink_story;
 mod ink_variables;
 
 pub(crate) use ink_asset_ready::*;
+pub use ink_binding_validation::*;
+#[cfg(feature = "threaded")]
+pub use ink_runtime::*;
+pub use ink_state_migrations::*;
+pub use ink_stories::*;
 pub use ink_story::*;
 pub use ink_variables::*;
@@ -0,0 +1,249 @@
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use bevy::prelude::*;
+use bevy_crossbeam_event::CrossbeamEventSender;
+
+use crate::{
+    events::{InkEntityEvent, InkStateUpdate},
+    resources::{InkStories, InkStoryId},
+};
+
+/// A change to the world, produced while running an operation on the stories.
+pub(crate) type InkEffect = Box<dyn FnOnce(&mut World) + Send>;
+
+/// An operation on the loaded stories, producing the effects to apply to the
+/// world once it has run.
+pub(crate) type InkOp = Box<dyn FnOnce(&mut InkStories) -> InkEffects + Send>;
+
+/// The effects produced by an [`InkOp`], applied to the world in order.
+#[derive(Default)]
+pub(crate) struct InkEffects(Vec<InkEffect>);
+
+impl InkEffects {
+    pub(crate) fn push(&mut self, effect: impl FnOnce(&mut World) + Send + 'static) {
+        self.0.push(Box::new(effect));
+    }
+
+    pub(crate) fn trigger<E: Event>(&mut self, event: E)
+    where
+        for<'a> E::Trigger<'a>: Default,
+    {
+        self.push(move |world| world.trigger(event));
+    }
+
+    /// Triggers the event, and if an entity is speaking in the sequence, a
+    /// copy of it targeting that entity.
+    pub(crate) fn trigger_sequence<E: Event + Clone>(&mut self, speaker: Option<Entity>, event: E)
+    where
+        for<'a> E::Trigger<'a>: Default,
+    {
+        if let Some(entity) = speaker {
+            self.trigger(event.clone());
+            self.trigger(InkEntityEvent { entity, event });
+        } else {
+            self.trigger(event);
+        }
+    }
+
+    /// Saves the state of the story, and publishes it through an
+    /// [`InkStateUpdate`], if anything observes it.
+    pub(crate) fn update_state(&mut self, stories: &mut InkStories, story: InkStoryId) {
+        if !stories.publishes_states() {
+            return;
+        }
+        let Some(Ok(state)) = stories.save_state(story) else {
+            return;
+        };
+        self.trigger(InkStateUpdate { story, state });
+    }
+
+    pub(crate) fn append(&mut self, mut effects: InkEffects) {
+        self.0.append(&mut effects.0);
+    }
+
+    pub(crate) fn apply(self, world: &mut World) {
+        for effect in self.0 {
+            effect(world);
+        }
+    }
+}
+
+thread_local! {
+    static BINDING_EVENTS: RefCell<Option<InkEffects>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, collecting the events triggered by bindings meanwhile, so they
+/// can be triggered in order with the content the story produced.
+pub(crate) fn collect_binding_events<R>(f: impl FnOnce() -> R) -> (R, InkEffects) {
+    let previous = BINDING_EVENTS.replace(Some(InkEffects::default()));
+    let result = f();
+    let collected = BINDING_EVENTS.replace(previous).unwrap_or_default();
+    (result, collected)
+}
+
+/// Collects the event triggered by a binding, handing it back if no events
+/// are being collected.
+pub(crate) fn collect_binding_event<E: Event>(event: E) -> Result<(), E>
+where
+    for<'a> E::Trigger<'a>: Default,
+{
+    BINDING_EVENTS.with_borrow_mut(|collected| match collected {
+        Some(collected) => {
+            collected.trigger(event);
+            Ok(())
+        }
+        None => Err(event),
+    })
+}
+
+/// The effects of a binding called outside of an ink command, sent to the main
+/// thread to be applied to the world.
+#[derive(Event, Clone)]
+pub(crate) struct InkDeferredEffects(Arc<Mutex<Option<InkEffects>>>);
+
+pub(crate) fn apply_deferred_effects(deferred: On<InkDeferredEffects>, mut commands: Commands) {
+    let effects = deferred
+        .0
+        .lock()
+        .ok()
+        .and_then(|mut effects| effects.take());
+    if let Some(effects) = effects {
+        commands.queue(move |world: &mut World| effects.apply(world));
+    }
+}
+
+/// Collects the event triggered by a binding, or sends it to the main thread
+/// if no events are being collected.
+pub(crate) fn trigger_binding_event<E: Event>(
+    sender: &CrossbeamEventSender<InkDeferredEffects>,
+    event: E,
+) where
+    for<'a> E::Trigger<'a>: Default,
+{
+    if let Err(event) = collect_binding_event(event) {
+        let mut effects = InkEffects::default();
+        effects.trigger(event);
+        sender.send(InkDeferredEffects(Arc::new(Mutex::new(Some(effects)))));
+    }
+}
+
+/// Returns `true` if the stories can be reached through [`run_ink`].
+pub(crate) fn ink_running(world: &World) -> bool {
+    #[cfg(not(feature = "threaded"))]
+    {
+        world.get_non_send_resource::<InkStories>().is_some()
+    }
+
+    #[cfg(feature = "threaded")]
+    {
+        world.contains_resource::<crate::resources::InkRuntime>()
+    }
+}
+
+/// Returns `true` if anything observes [`InkStateUpdate`], which the state of
+/// the stories is only serialized for then.
+fn state_updates_observed(world: &World) -> bool {
+    world
+        .event_key::<InkStateUpdate>()
+        .and_then(|key| world.observers().try_get_observers(key))
+        .is_some_and(|observers| !observers.global_observers().is_empty())
+}
+
+/// Wraps the operation so that the events of the variables and bindings it
+/// changes or calls are applied in order with its other effects, rather than
+/// through the channels which could overtake them.
+fn prepare_op(
+    world: &World,
+    op: impl FnOnce(&mut InkStories) -> InkEffects + Send + 'static,
+) -> impl FnOnce(&mut InkStories) -> InkEffects + Send + 'static {
+    let publish_states = state_updates_observed(world);
+    move |stories: &mut InkStories| {
+        stories.set_publish_states(publish_states);
+        let (effects, mut collected) = collect_binding_events(|| op(stories));
+        collected.append(effects);
+        collected
+    }
+}
+
+/// Runs the operation against the loaded stories, then applies its effects to
+/// the world.
+#[cfg(not(feature = "threaded"))]
+pub(crate) fn run_ink(
+    world: &mut World,
+    op: impl FnOnce(&mut InkStories) -> InkEffects + Send + 'static,
+) {
+    let op = prepare_op(world, op);
+    if !world.contains_resource::<crate::ink::InkSystemBindings>() {
+        let Some(mut stories) = world.get_non_send_resource_mut::<InkStories>() else {
+            error!("Failed to access ink stories, did you forget to initialize the InkPlugin?");
+            return;
+        };
+        let effects = op(&mut stories);
+        effects.apply(world);
+        return;
+    }
+
+    // system bindings need the world while the story runs, so the stories are
+    // taken out of it, and the world is lent to the bindings
+    let Some(mut stories) = world.remove_non_send_resource::<InkStories>() else {
+        // commands of a system binding run once the story it was called from
+        // is done, as they would in the threaded runtime
+        let op: InkOp = Box::new(op);
+        if !crate::ink::queue_lent_op(move |world| run_ink(world, op)) {
+            error!("Failed to access ink stories, did you forget to initialize the InkPlugin?");
+        }
+        return;
+    };
+    let (effects, lent_ops) = crate::ink::lend_world(world, || op(&mut stories));
+    world.insert_non_send_resource(stories);
+    effects.apply(world);
+    lent_ops.apply(world);
+}
+
+/// Sends the operation to the worker thread owning the stories. Its effects
+/// are applied to the world by [`apply_ink_effects`] once it has run.
+#[cfg(feature = "threaded")]
+pub(crate) fn run_ink(
+    world: &mut World,
+    op: impl FnOnce(&mut InkStories) -> InkEffects + Send + 'static,
+) {
+    let op = prepare_op(world, op);
+    let Some(runtime) = world.get_resource::<crate::resources::InkRuntime>() else {
+        error!("Failed to access ink runtime, did you forget to initialize the InkPlugin?");
+        return;
+    };
+    runtime.send(Box::new(op));
+}
+
+/// Runs the system bindings the worker thread is waiting on, then applies the
+/// effects of every operation it has finished.
+#[cfg(feature = "threaded")]
+pub(crate) fn apply_ink_effects(world: &mut World) {
+    let calls = world
+        .resource::<crate::resources::InkRuntime>()
+        .pending_calls();
+    for call in calls {
+        call.run(world);
+    }
+    let effects = world.resource::<crate::resources::InkRuntime>().finished();
+    for effects in effects {
+        effects.apply(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_updates_observed() {
+        let mut world = World::new();
+        assert!(!state_updates_observed(&world));
+        world.add_observer(|_: On<InkStateUpdate>| {});
+        world.flush();
+        assert!(state_updates_observed(&world));
+    }
+}
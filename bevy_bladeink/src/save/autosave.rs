@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    events::{DeliverChoices, DeliverLine, StoryReady},
+    resources::InkStoryId,
+    save::{InkSaveError, InkSaves, SaveToSlotCommand},
+};
+
+/// The tag marking the lines a story is saved on, with
+/// [`InkAutosavePolicy::OnTaggedLine`].
+pub const INK_AUTOSAVE_TAG: &str = "autosave";
+
+/// When stories are saved automatically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InkAutosavePolicy {
+    /// Every time the story has delivered the given number of lines.
+    EveryLines(u32),
+    /// Every time the story delivers choices.
+    OnChoice,
+    /// Every time a line carries the `#autosave` tag. The tags at the top of a
+    /// knot are carried by its first line, so tagging a knot saves the story
+    /// on entering it.
+    OnTaggedLine,
+    /// Once the given duration has passed since the story first delivered a
+    /// line or choices after its last autosave, so unchanged stories aren't
+    /// saved again.
+    Timer(Duration),
+}
+
+/// Saves the stories automatically to a rolling set of slots, replacing the
+/// oldest autosave each time. Insert it along with the
+/// [`InkSavePlugin`](crate::save::InkSavePlugin) to enable autosaves.
+///
+/// The slots of the default story are named `autosave-0`, `autosave-1`, …,
+/// and those of other stories include their id, e.g. `autosave-1-0`.
+#[derive(Resource, Clone, Debug)]
+pub struct InkAutosave {
+    pub policy: InkAutosavePolicy,
+    /// The number of autosave slots of each story.
+    pub slots: usize,
+    /// The prefix of the names of the autosave slots.
+    pub prefix: String,
+}
+
+impl InkAutosave {
+    /// Autosaves following the policy, to three slots.
+    pub fn new(policy: InkAutosavePolicy) -> Self {
+        Self {
+            policy,
+            slots: 3,
+            prefix: "autosave".to_string(),
+        }
+    }
+
+    pub fn with_slots(mut self, slots: usize) -> Self {
+        self.slots = slots.max(1);
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// The name of an autosave slot of the story.
+    pub fn slot(&self, story: InkStoryId, index: usize) -> String {
+        if story == InkStoryId::DEFAULT {
+            format!("{}-{index}", self.prefix)
+        } else {
+            format!("{}-{}-{index}", self.prefix, story.0)
+        }
+    }
+}
+
+/// The progress of each story towards its next autosave.
+#[derive(Resource, Default)]
+pub(crate) struct InkAutosaveProgress {
+    stories: HashMap<InkStoryId, AutosaveProgress>,
+}
+
+#[derive(Default)]
+struct AutosaveProgress {
+    lines: u32,
+    /// The time since the story first changed after its last autosave, if it
+    /// has.
+    elapsed: Option<Duration>,
+    /// The slot the next autosave is written to, once known.
+    next_slot: Option<usize>,
+}
+
+pub(crate) fn start_autosaves(ready: On<StoryReady>, mut progress: ResMut<InkAutosaveProgress>) {
+    progress
+        .stories
+        .insert(ready.story, AutosaveProgress::default());
+}
+
+pub(crate) fn autosave_on_line(
+    line: On<DeliverLine>,
+    autosave: Option<Res<InkAutosave>>,
+    mut progress: ResMut<InkAutosaveProgress>,
+    mut commands: Commands,
+) {
+    let Some(autosave) = autosave else {
+        return;
+    };
+    let Some(story) = progress.stories.get_mut(&line.story) else {
+        return;
+    };
+    story.elapsed.get_or_insert_default();
+    let save = match autosave.policy {
+        InkAutosavePolicy::EveryLines(lines) => {
+            story.lines += 1;
+            story.lines >= lines
+        }
+        InkAutosavePolicy::OnTaggedLine => {
+            line.tags.iter().any(|tag| tag.trim() == INK_AUTOSAVE_TAG)
+        }
+        _ => false,
+    };
+    if save {
+        story.lines = 0;
+        commands.queue(AutosaveCommand { story: line.story });
+    }
+}
+
+pub(crate) fn autosave_on_choices(
+    choices: On<DeliverChoices>,
+    autosave: Option<Res<InkAutosave>>,
+    mut progress: ResMut<InkAutosaveProgress>,
+    mut commands: Commands,
+) {
+    let Some(story) = progress.stories.get_mut(&choices.story) else {
+        return;
+    };
+    story.elapsed.get_or_insert_default();
+    if let Some(autosave) = autosave
+        && autosave.policy == InkAutosavePolicy::OnChoice
+    {
+        commands.queue(AutosaveCommand {
+            story: choices.story,
+        });
+    }
+}
+
+pub(crate) fn autosave_on_timer(
+    time: Res<Time>,
+    autosave: Option<Res<InkAutosave>>,
+    mut progress: ResMut<InkAutosaveProgress>,
+    mut commands: Commands,
+) {
+    let Some(InkAutosavePolicy::Timer(interval)) = autosave.map(|autosave| autosave.policy.clone())
+    else {
+        return;
+    };
+    for (id, story) in &mut progress.stories {
+        let Some(elapsed) = &mut story.elapsed else {
+            continue;
+        };
+        *elapsed += time.delta();
+        if *elapsed >= interval {
+            story.elapsed = None;
+            commands.queue(AutosaveCommand { story: *id });
+        }
+    }
+}
+
+/// Saves the story to the next of its autosave slots.
+struct AutosaveCommand {
+    story: InkStoryId,
+}
+
+impl Command for AutosaveCommand {
+    fn apply(self, world: &mut World) {
+        let Some(autosave) = world.get_resource::<InkAutosave>().cloned() else {
+            return;
+        };
+        let Some(saves) = world.get_resource::<InkSaves>().cloned() else {
+            error!("InkSaves resource not found. Did you forget to add the InkSavePlugin?");
+            return;
+        };
+        let slots = autosave.slots.max(1);
+        let mut progress = world.resource_mut::<InkAutosaveProgress>();
+        let story = progress.stories.entry(self.story).or_default();
+        let index = story
+            .next_slot
+            .unwrap_or_else(|| oldest_slot(&saves, &autosave, self.story))
+            % slots;
+        story.next_slot = Some((index + 1) % slots);
+
+        SaveToSlotCommand {
+            story: self.story,
+            slot: autosave.slot(self.story, index),
+        }
+        .apply(world);
+    }
+}
+
+/// The autosave slot to write first, either an empty one, or the one holding
+/// the oldest save, e.g. from a previous session.
+fn oldest_slot(saves: &InkSaves, autosave: &InkAutosave, story: InkStoryId) -> usize {
+    let mut oldest = (0, u64::MAX);
+    for index in 0..autosave.slots {
+        match saves.read(&autosave.slot(story, index)) {
+            Ok(save) if save.metadata.saved_at < oldest.1 => {
+                oldest = (index, save.metadata.saved_at);
+            }
+            Ok(_) => {}
+            Err(InkSaveError::NotFound(_)) => return index,
+            Err(err) => {
+                warn!("Replacing unreadable autosave: {err}");
+                return index;
+            }
+        }
+    }
+    oldest.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autosave_slot_names() {
+        let autosave = InkAutosave::new(InkAutosavePolicy::OnChoice).with_slots(0);
+        assert_eq!(autosave.slots, 1);
+        assert_eq!(autosave.slot(InkStoryId::DEFAULT, 0), "autosave-0");
+        assert_eq!(
+            autosave.with_prefix("auto").slot(InkStoryId(2), 1),
+            "auto-2-1"
+        );
+    }
+}
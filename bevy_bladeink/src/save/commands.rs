@@ -0,0 +1,222 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bladeink::story::Story;
+
+use crate::{
+    commands::{InkCommandQueue, load_state},
+    resources::{InkStateMigrations, InkStoryId},
+    runtime::{InkEffects, run_ink},
+    save::{
+        InkPlaythrough, InkSave, InkSaveError, InkSaves, InkSlotDeleted, InkSlotFailed,
+        InkSlotLoaded, InkSlotMetadata, InkSlotSaved, InkStoryPlaythrough,
+    },
+};
+
+/// Reports that an operation on a slot failed.
+fn fail_slot(effects: &mut InkEffects, story: InkStoryId, slot: String, error: InkSaveError) {
+    error!("Failed to use save slot: {error}");
+    effects.trigger(InkSlotFailed { story, slot, error });
+}
+
+fn saves(world: &World) -> Option<InkSaves> {
+    let saves = world.get_resource::<InkSaves>().cloned();
+    if saves.is_none() {
+        error!("InkSaves resource not found. Did you forget to add the InkSavePlugin?");
+    }
+    saves
+}
+
+pub(crate) struct SaveToSlotCommand {
+    pub story: InkStoryId,
+    pub slot: String,
+}
+
+impl Command for SaveToSlotCommand {
+    fn apply(self, world: &mut World) {
+        let Some(saves) = saves(world) else {
+            return;
+        };
+
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            let path = stories.get(self.story).and_then(Story::get_current_path);
+            let state = match stories.save_state(self.story) {
+                Some(Ok(state)) => state,
+                Some(Err(err)) => {
+                    let error = InkSaveError::Save(err.to_string());
+                    fail_slot(&mut effects, self.story, self.slot, error);
+                    return effects;
+                }
+                None => {
+                    error!(
+                        "Failed to save to slot: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                        self.story
+                    );
+                    return effects;
+                }
+            };
+            // the playthrough is read once the lines delivered before saving
+            // have been recorded
+            effects.push(move |world| {
+                let playthrough = world
+                    .get_resource::<InkPlaythrough>()
+                    .and_then(|playthrough| playthrough.story(self.story))
+                    .cloned()
+                    .unwrap_or_default();
+                let saved_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                let save = InkSave {
+                    metadata: InkSlotMetadata {
+                        slot: self.slot,
+                        story: self.story,
+                        saved_at,
+                        path,
+                        last_line: playthrough.last_line,
+                        playtime: playthrough.playtime,
+                    },
+                    state,
+                };
+                let mut effects = InkEffects::default();
+                match saves.write(&save) {
+                    Ok(()) => effects.trigger(InkSlotSaved {
+                        story: self.story,
+                        metadata: save.metadata,
+                    }),
+                    Err(error) => fail_slot(&mut effects, self.story, save.metadata.slot, error),
+                }
+                effects.apply(world);
+            });
+            effects
+        });
+    }
+}
+
+pub(crate) struct LoadFromSlotCommand {
+    pub story: InkStoryId,
+    pub slot: String,
+}
+
+impl Command for LoadFromSlotCommand {
+    fn apply(self, world: &mut World) {
+        let Some(saves) = saves(world) else {
+            return;
+        };
+        let migration = world
+            .get_resource::<InkStateMigrations>()
+            .and_then(|migrations| migrations.get(self.story))
+            .cloned();
+
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            if !stories.contains(self.story) {
+                error!(
+                    "Failed to load from slot: Story {:?} not found. Did you forget to insert the InkProject resource?",
+                    self.story
+                );
+                return effects;
+            }
+            let save = match saves.read(&self.slot) {
+                Ok(save) if save.metadata.story != self.story => {
+                    let error = InkSaveError::OtherStory {
+                        slot: self.slot.clone(),
+                        saved: save.metadata.story,
+                    };
+                    fail_slot(&mut effects, self.story, self.slot, error);
+                    return effects;
+                }
+                Ok(save) => save,
+                Err(error) => {
+                    fail_slot(&mut effects, self.story, self.slot, error);
+                    return effects;
+                }
+            };
+            if let Err(error) = load_state(stories, self.story, save.state, migration, &mut effects)
+            {
+                fail_slot(&mut effects, self.story, self.slot, error.into());
+                return effects;
+            }
+
+            let story = self.story;
+            let metadata = save.metadata;
+            let playthrough = InkStoryPlaythrough {
+                playtime: metadata.playtime,
+                last_line: metadata.last_line.clone(),
+            };
+            effects.push(move |world| {
+                if let Some(mut playthroughs) = world.get_resource_mut::<InkPlaythrough>() {
+                    playthroughs.insert(story, playthrough);
+                }
+            });
+            effects.trigger(InkSlotLoaded { story, metadata });
+            effects
+        });
+    }
+}
+
+pub(crate) struct DeleteSlotCommand {
+    pub story: InkStoryId,
+    pub slot: String,
+}
+
+impl Command for DeleteSlotCommand {
+    fn apply(self, world: &mut World) {
+        let Some(saves) = saves(world) else {
+            return;
+        };
+
+        // deleted in order with the commands queued before, which may read it
+        run_ink(world, move |_| {
+            let mut effects = InkEffects::default();
+            match saves.storage().delete(&self.slot) {
+                Ok(()) => effects.trigger(InkSlotDeleted { slot: self.slot }),
+                Err(error) => fail_slot(&mut effects, self.story, self.slot, error),
+            }
+            effects
+        });
+    }
+}
+
+/// Commands for the save slots of the
+/// [`InkSavePlugin`](crate::save::InkSavePlugin).
+pub trait InkSaveCommandsExt {
+    /// Saves the state of the story to the slot, replacing any previous save.
+    fn ink_save_to_slot(&mut self, slot: impl Into<String>) -> &mut Self;
+
+    /// Loads the state of the story from the slot.
+    fn ink_load_from_slot(&mut self, slot: impl Into<String>) -> &mut Self;
+
+    /// Deletes the save held by the slot.
+    fn ink_delete_slot(&mut self, slot: impl Into<String>) -> &mut Self;
+}
+
+impl<T: InkCommandQueue> InkSaveCommandsExt for T {
+    fn ink_save_to_slot(&mut self, slot: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(SaveToSlotCommand {
+            story,
+            slot: slot.into(),
+        });
+        self
+    }
+
+    fn ink_load_from_slot(&mut self, slot: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(LoadFromSlotCommand {
+            story,
+            slot: slot.into(),
+        });
+        self
+    }
+
+    fn ink_delete_slot(&mut self, slot: impl Into<String>) -> &mut Self {
+        let story = self.ink_story_id();
+        self.queue_ink_command(DeleteSlotCommand {
+            story,
+            slot: slot.into(),
+        });
+        self
+    }
+}
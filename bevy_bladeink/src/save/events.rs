@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use crate::{
+    resources::InkStoryId,
+    save::{InkSaveError, InkSlotMetadata},
+};
+
+/// Emitted once the state of a story has been written to a slot.
+#[derive(Event, Clone, Debug)]
+pub struct InkSlotSaved {
+    pub story: InkStoryId,
+    pub metadata: InkSlotMetadata,
+}
+
+/// Emitted once the state of a story has been loaded from a slot.
+#[derive(Event, Clone, Debug)]
+pub struct InkSlotLoaded {
+    pub story: InkStoryId,
+    pub metadata: InkSlotMetadata,
+}
+
+/// Emitted once a slot has been deleted.
+#[derive(Event, Clone, Debug)]
+pub struct InkSlotDeleted {
+    pub slot: String,
+}
+
+/// Emitted when saving to, loading from, or deleting a slot fails.
+#[derive(Event, Clone, Debug)]
+pub struct InkSlotFailed {
+    pub story: InkStoryId,
+    pub slot: String,
+    pub error: InkSaveError,
+}
@@ -0,0 +1,44 @@
+//! ## Save slots
+//!
+//! The [`InkSavePlugin`] stores the state of stories in named slots, along
+//! with metadata describing each save, e.g. for a load menu. Where the slots
+//! are stored is up to an [`InkSaveStorage`], either the filesystem or memory.
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_bladeink::prelude::*;
+//!
+//! fn save(mut commands: Commands) {
+//!     commands.ink_save_to_slot("quicksave");
+//! }
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins(DefaultPlugins)
+//!         .add_plugins(InkPlugin)
+//!         .add_plugins(InkSavePlugin::filesystem("saves"))
+//!         .add_systems(Update, save.run_if(run_once))
+//!         .run();
+//! }
+//! ```
+//!
+//! Inserting an [`InkAutosave`] also saves the stories automatically, to a
+//! rolling set of slots, e.g. every time they deliver choices:
+//!
+//! ```rust,ignore
+//! app.insert_resource(InkAutosave::new(InkAutosavePolicy::OnChoice).with_slots(3));
+//! ```
+
+mod autosave;
+mod commands;
+mod events;
+mod plugin;
+mod slot;
+mod storage;
+
+pub use autosave::*;
+pub use commands::*;
+pub use events::*;
+pub use plugin::*;
+pub use slot::*;
+pub use storage::*;
@@ -0,0 +1,135 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    events::{DeliverLine, StoryReady},
+    resources::InkStoryId,
+    save::{
+        InkAutosaveProgress, InkFileStorage, InkMemoryStorage, InkSave, InkSaveError,
+        InkSaveStorage, InkSlotMetadata, autosave_on_choices, autosave_on_line, autosave_on_timer,
+        start_autosaves,
+    },
+};
+
+/// Plugin storing the state of stories in save slots, through the commands of
+/// [`InkSaveCommandsExt`](crate::save::InkSaveCommandsExt). Must be added
+/// after the `InkPlugin`.
+pub struct InkSavePlugin {
+    storage: Arc<dyn InkSaveStorage>,
+}
+
+impl InkSavePlugin {
+    pub fn new(storage: impl InkSaveStorage) -> Self {
+        Self {
+            storage: Arc::new(storage),
+        }
+    }
+
+    /// Stores the saves as files in the given directory.
+    pub fn filesystem(directory: impl Into<PathBuf>) -> Self {
+        Self::new(InkFileStorage::new(directory))
+    }
+
+    /// Keeps the saves in memory, e.g. for tests.
+    pub fn memory() -> Self {
+        Self::new(InkMemoryStorage::default())
+    }
+}
+
+impl Plugin for InkSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InkSaves {
+            storage: self.storage.clone(),
+        })
+        .init_resource::<InkPlaythrough>()
+        .init_resource::<InkAutosaveProgress>()
+        .add_observer(start_playthrough)
+        .add_observer(record_last_line)
+        .add_observer(start_autosaves)
+        .add_observer(autosave_on_line)
+        .add_observer(autosave_on_choices)
+        .add_systems(Update, (track_playtime, autosave_on_timer));
+    }
+}
+
+/// The save slots, stored through an [`InkSaveStorage`].
+#[derive(Resource, Clone)]
+pub struct InkSaves {
+    storage: Arc<dyn InkSaveStorage>,
+}
+
+impl InkSaves {
+    pub fn storage(&self) -> &dyn InkSaveStorage {
+        self.storage.as_ref()
+    }
+
+    /// Reads the save held by a slot.
+    pub fn read(&self, slot: &str) -> Result<InkSave, InkSaveError> {
+        InkSave::from_bytes(&self.storage.read(slot)?)
+    }
+
+    /// The metadata of every slot, e.g. for a load menu. Slots which can't be
+    /// read are skipped.
+    pub fn slots(&self) -> Result<Vec<InkSlotMetadata>, InkSaveError> {
+        let slots = self
+            .storage
+            .slots()?
+            .into_iter()
+            .filter_map(|slot| {
+                self.read(&slot)
+                    .inspect_err(|err| warn!("Failed to read save slot '{slot}': {err}"))
+                    .ok()
+            })
+            .map(|save| save.metadata)
+            .collect();
+        Ok(slots)
+    }
+
+    pub(crate) fn write(&self, save: &InkSave) -> Result<(), InkSaveError> {
+        self.storage.write(&save.metadata.slot, &save.to_bytes()?)
+    }
+}
+
+/// The progress through each story which isn't part of its state, recorded
+/// in the metadata of its saves.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct InkPlaythrough {
+    stories: HashMap<InkStoryId, InkStoryPlaythrough>,
+}
+
+/// The progress through a single story.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct InkStoryPlaythrough {
+    /// How long the story has been played for, since it was ready or loaded
+    /// from a slot.
+    pub playtime: Duration,
+    /// The last line the story delivered.
+    pub last_line: Option<String>,
+}
+
+impl InkPlaythrough {
+    pub fn story(&self, id: InkStoryId) -> Option<&InkStoryPlaythrough> {
+        self.stories.get(&id)
+    }
+
+    pub(crate) fn insert(&mut self, id: InkStoryId, playthrough: InkStoryPlaythrough) {
+        self.stories.insert(id, playthrough);
+    }
+}
+
+fn start_playthrough(ready: On<StoryReady>, mut playthrough: ResMut<InkPlaythrough>) {
+    playthrough.insert(ready.story, InkStoryPlaythrough::default());
+}
+
+fn record_last_line(line: On<DeliverLine>, mut playthrough: ResMut<InkPlaythrough>) {
+    if let Some(story) = playthrough.stories.get_mut(&line.story) {
+        story.last_line = Some(line.text.trim().to_string());
+    }
+}
+
+fn track_playtime(time: Res<Time>, mut playthrough: ResMut<InkPlaythrough>) {
+    for story in playthrough.stories.values_mut() {
+        story.playtime += time.delta();
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ink::InkState, resources::InkStoryId, save::InkSaveError};
+
+/// Describes the save held by a slot, e.g. for a load menu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InkSlotMetadata {
+    pub slot: String,
+    /// The story which was saved, which is the only one the slot can be
+    /// loaded into.
+    #[serde(default)]
+    pub story: InkStoryId,
+    /// When the save was made, in seconds since the Unix epoch.
+    pub saved_at: u64,
+    /// Where the story was when it was saved, e.g. `market.stall`.
+    pub path: Option<String>,
+    /// The last line the story delivered before it was saved.
+    pub last_line: Option<String>,
+    /// How long the story had been played for, including the playtime of the
+    /// save it was loaded from.
+    pub playtime: Duration,
+}
+
+/// The content of a save slot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InkSave {
+    pub metadata: InkSlotMetadata,
+    pub state: InkState,
+}
+
+impl InkSave {
+    pub(crate) fn to_bytes(&self) -> Result<Vec<u8>, InkSaveError> {
+        serde_json::to_vec(self).map_err(|err| InkSaveError::Format(err.to_string()))
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, InkSaveError> {
+        serde_json::from_slice(bytes).map_err(|err| InkSaveError::Format(err.to_string()))
+    }
+}
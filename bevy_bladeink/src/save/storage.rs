@@ -0,0 +1,220 @@
+use std::{
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use bevy::platform::collections::HashMap;
+use thiserror::Error;
+
+use crate::{ink::InkStateError, resources::InkStoryId};
+
+/// Error saving a story to a slot, or loading it from one.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum InkSaveError {
+    /// The slot holds no save.
+    #[error("Save slot '{0}' not found")]
+    NotFound(String),
+    /// The name of the slot can't be stored.
+    #[error("Invalid save slot name '{0}'")]
+    InvalidSlot(String),
+    /// The storage failed to read or write the slot.
+    #[error("Save storage error: {0}")]
+    Storage(String),
+    /// The slot doesn't hold a valid save.
+    #[error("Invalid save: {0}")]
+    Format(String),
+    /// The state of the story could not be saved.
+    #[error("Failed to save the state of the story: {0}")]
+    Save(String),
+    /// The slot holds the save of another story.
+    #[error("Save slot '{slot}' holds a save of story {saved:?}")]
+    OtherStory { slot: String, saved: InkStoryId },
+    /// The saved state could not be loaded into the story.
+    #[error("{0}")]
+    Load(#[from] InkStateError),
+}
+
+/// Where the [`InkSavePlugin`](crate::save::InkSavePlugin) stores the saves
+/// of each slot.
+pub trait InkSaveStorage: Send + Sync + 'static {
+    /// Writes the save of the slot, replacing any previous one.
+    fn write(&self, slot: &str, data: &[u8]) -> Result<(), InkSaveError>;
+
+    /// Reads the save of the slot, failing with [`InkSaveError::NotFound`] if
+    /// there is none.
+    fn read(&self, slot: &str) -> Result<Vec<u8>, InkSaveError>;
+
+    /// Deletes the save of the slot, failing with [`InkSaveError::NotFound`]
+    /// if there is none.
+    fn delete(&self, slot: &str) -> Result<(), InkSaveError>;
+
+    /// The names of the slots holding a save.
+    fn slots(&self) -> Result<Vec<String>, InkSaveError>;
+}
+
+/// Keeps the saves in memory, e.g. for tests.
+#[derive(Default, Clone)]
+pub struct InkMemoryStorage {
+    slots: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+}
+
+impl InkSaveStorage for InkMemoryStorage {
+    fn write(&self, slot: &str, data: &[u8]) -> Result<(), InkSaveError> {
+        self.slots
+            .lock()
+            .map_err(|err| InkSaveError::Storage(err.to_string()))?
+            .insert(slot.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn read(&self, slot: &str) -> Result<Vec<u8>, InkSaveError> {
+        self.slots
+            .lock()
+            .map_err(|err| InkSaveError::Storage(err.to_string()))?
+            .get(slot)
+            .cloned()
+            .ok_or_else(|| InkSaveError::NotFound(slot.to_string()))
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), InkSaveError> {
+        self.slots
+            .lock()
+            .map_err(|err| InkSaveError::Storage(err.to_string()))?
+            .remove(slot)
+            .map(|_| ())
+            .ok_or_else(|| InkSaveError::NotFound(slot.to_string()))
+    }
+
+    fn slots(&self) -> Result<Vec<String>, InkSaveError> {
+        let slots = self
+            .slots
+            .lock()
+            .map_err(|err| InkSaveError::Storage(err.to_string()))?;
+        let mut names: Vec<_> = slots.keys().cloned().collect();
+        names.sort();
+        Ok(names)
+    }
+}
+
+/// Stores each save as a `<slot>.json` file in a directory, created when the
+/// first save is written. Slot names are limited to letters, digits, `-` and
+/// `_`.
+#[derive(Debug, Clone)]
+pub struct InkFileStorage {
+    directory: PathBuf,
+}
+
+impl InkFileStorage {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn path(&self, slot: &str) -> Result<PathBuf, InkSaveError> {
+        let valid = !slot.is_empty()
+            && slot
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(InkSaveError::InvalidSlot(slot.to_string()));
+        }
+        Ok(self.directory.join(format!("{slot}.json")))
+    }
+}
+
+fn storage_error(slot: &str, err: std::io::Error) -> InkSaveError {
+    match err.kind() {
+        ErrorKind::NotFound => InkSaveError::NotFound(slot.to_string()),
+        _ => InkSaveError::Storage(err.to_string()),
+    }
+}
+
+impl InkSaveStorage for InkFileStorage {
+    fn write(&self, slot: &str, data: &[u8]) -> Result<(), InkSaveError> {
+        let path = self.path(slot)?;
+        std::fs::create_dir_all(&self.directory)
+            .map_err(|err| InkSaveError::Storage(err.to_string()))?;
+        // written next to the save first, so a failed write keeps the
+        // previous save intact
+        let temporary = path.with_extension("json.tmp");
+        std::fs::write(&temporary, data).map_err(|err| storage_error(slot, err))?;
+        std::fs::rename(&temporary, &path).map_err(|err| storage_error(slot, err))
+    }
+
+    fn read(&self, slot: &str) -> Result<Vec<u8>, InkSaveError> {
+        std::fs::read(self.path(slot)?).map_err(|err| storage_error(slot, err))
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), InkSaveError> {
+        std::fs::remove_file(self.path(slot)?).map_err(|err| storage_error(slot, err))
+    }
+
+    fn slots(&self) -> Result<Vec<String>, InkSaveError> {
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(InkSaveError::Storage(err.to_string())),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| InkSaveError::Storage(err.to_string()))?
+                .path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && let Some(name) = path.file_stem().and_then(|name| name.to_str())
+            {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(storage: &impl InkSaveStorage) {
+        assert_eq!(storage.slots().unwrap(), Vec::<String>::new());
+        assert_eq!(
+            storage.read("one"),
+            Err(InkSaveError::NotFound("one".to_string()))
+        );
+
+        storage.write("one", b"first").unwrap();
+        storage.write("two", b"second").unwrap();
+        storage.write("one", b"third").unwrap();
+        assert_eq!(storage.read("one").unwrap(), b"third");
+        assert_eq!(storage.slots().unwrap(), vec!["one", "two"]);
+
+        storage.delete("one").unwrap();
+        assert_eq!(
+            storage.delete("one"),
+            Err(InkSaveError::NotFound("one".to_string()))
+        );
+        assert_eq!(storage.slots().unwrap(), vec!["two"]);
+    }
+
+    #[test]
+    fn test_memory_storage() {
+        exercise(&InkMemoryStorage::default());
+    }
+
+    #[test]
+    fn test_file_storage() {
+        let directory =
+            std::env::temp_dir().join(format!("bevy_bladeink_saves_{}", std::process::id()));
+        let storage = InkFileStorage::new(&directory);
+        exercise(&storage);
+        assert_eq!(
+            storage.write("../escape", b""),
+            Err(InkSaveError::InvalidSlot("../escape".to_string()))
+        );
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,43 +1,116 @@
-use bevy::prelude::*;
+use bevy::{
+    asset::{AssetLoadError, AssetLoadFailedEvent},
+    prelude::*,
+};
 
 use crate::{
-    assets::StoryJson,
-    resources::{InkAssetReady, InkStory},
+    assets::{InkSourceLoaderError, InkStoryJsonLoaderError, StoryJson},
+    events::InkLoadStage,
+    resources::{InkAssetReady, InkLoadProgress, InkStory, InkStoryId, InkStoryRegistry},
+    systems::fail_story_load,
 };
 
 pub(crate) fn load_ink_project(
     asset_server: Res<AssetServer>,
     ink_project: Option<ResMut<InkStory>>,
+    registry: Option<ResMut<InkStoryRegistry>>,
 ) {
-    let Some(mut ink_project) = ink_project else {
-        return;
-    };
+    let default_story = ink_project.map(ResMut::into_inner);
+    let registered_stories = registry.map(ResMut::into_inner);
 
-    if ink_project.handle().is_none() {
-        let path = ink_project.asset_path().to_string();
-        ink_project.set_handle(asset_server.load(path));
+    for ink_project in default_story.into_iter().chain(
+        registered_stories
+            .into_iter()
+            .flat_map(|r| r.iter_mut().map(|(_, s)| s)),
+    ) {
+        if ink_project.handle().is_none() {
+            let path = ink_project.asset_path().to_string();
+            ink_project.set_handle(asset_server.load(path));
+            ink_project.set_load_progress(InkLoadProgress::LoadingAsset);
+        }
     }
 }
 
 pub(crate) fn process_story_json_events(
-    mut commands: Commands,
     mut asset_messages: MessageReader<AssetEvent<StoryJson>>,
     ink_project: Option<Res<InkStory>>,
+    registry: Option<Res<InkStoryRegistry>>,
+    mut ready: ResMut<InkAssetReady>,
 ) {
-    let Some(ink_project) = ink_project else {
+    let stories: Vec<(InkStoryId, &InkStory)> = ink_project
+        .as_deref()
+        .map(|story| (InkStoryId::DEFAULT, story))
+        .into_iter()
+        .chain(
+            registry
+                .as_deref()
+                .into_iter()
+                .flat_map(InkStoryRegistry::iter),
+        )
+        .collect();
+
+    if stories.is_empty() {
         return;
-    };
+    }
 
     for message in asset_messages.read() {
         match message {
             AssetEvent::Modified { id } | AssetEvent::LoadedWithDependencies { id } => {
-                if let Some(handle) = ink_project.handle()
-                    && handle.id() == *id
-                {
-                    commands.insert_resource(InkAssetReady(handle.clone()));
+                for (story_id, ink_project) in stories.iter() {
+                    if let Some(handle) = ink_project.handle()
+                        && handle.id() == *id
+                    {
+                        ready.0.insert(*story_id, handle.clone());
+                    }
                 }
             }
             AssetEvent::Added { .. } | AssetEvent::Removed { .. } | AssetEvent::Unused { .. } => {}
         }
     }
 }
+
+pub(crate) fn process_story_load_failures(
+    mut failures: MessageReader<AssetLoadFailedEvent<StoryJson>>,
+    ink_project: Option<Res<InkStory>>,
+    registry: Option<Res<InkStoryRegistry>>,
+    mut commands: Commands,
+) {
+    for failure in failures.read() {
+        let failed = ink_project
+            .as_deref()
+            .map(|story| (InkStoryId::DEFAULT, story))
+            .into_iter()
+            .chain(
+                registry
+                    .as_deref()
+                    .into_iter()
+                    .flat_map(InkStoryRegistry::iter),
+            )
+            .filter(|(_, ink_project)| {
+                ink_project
+                    .handle()
+                    .is_some_and(|handle| handle.id() == failure.id)
+            });
+        for (story_id, _) in failed {
+            let stage = load_stage(&failure.error);
+            let error = failure.error.to_string();
+            commands.queue(move |world: &mut World| {
+                fail_story_load(world, story_id, stage, error);
+            });
+        }
+    }
+}
+
+/// The step of loading a story which produced the error.
+fn load_stage(error: &AssetLoadError) -> InkLoadStage {
+    let AssetLoadError::AssetLoaderError(error) = error else {
+        return InkLoadStage::AssetIo;
+    };
+    if let Some(err) = error.error().downcast_ref::<InkStoryJsonLoaderError>() {
+        return err.stage();
+    }
+    if let Some(err) = error.error().downcast_ref::<InkSourceLoaderError>() {
+        return err.stage();
+    }
+    InkLoadStage::AssetIo
+}
@@ -1,7 +1,7 @@
 mod ink_story;
-mod state;
 mod story;
+mod variables;
 
 pub(crate) use ink_story::*;
-pub(crate) use state::*;
 pub(crate) use story::*;
+pub(crate) use variables::*;
@@ -1,34 +1,258 @@
 use bevy::prelude::*;
+use bevy_crossbeam_event::CrossbeamEventSender;
 
 use crate::{
     assets::StoryJson,
-    events::StoryReady,
-    ink::{InkBindingMap, create_story},
-    resources::{InkAssetReady, InkStory},
+    commands::{VariableUpdated, migrate_state, observe_variable},
+    events::{InkLoadStage, StoryLoadFailed, StoryReady, StoryReloaded},
+    ink::{InkBindingMap, InkStoryBindingMap, create_story, hash_story, validate_bindings},
+    resources::{
+        InkAssetReady, InkBindingValidation, InkBoundFunctions, InkLoadProgress,
+        InkStateMigrations, InkStories, InkStory, InkStoryId, InkStoryRegistry, InkVariables,
+    },
+    runtime::{InkEffects, collect_binding_events, run_ink},
 };
 
 pub(crate) fn parse_story_asset(world: &mut World) {
-    let Some(ink_project) = world.get_resource::<InkStory>() else {
-        return;
-    };
+    let InkAssetReady(ready) = world.resource::<InkAssetReady>();
+    let ready: Vec<_> = ready
+        .iter()
+        .map(|(id, handle)| (*id, handle.clone()))
+        .collect();
 
-    let Some(InkAssetReady(story_handle)) = world.get_resource::<InkAssetReady>() else {
-        return;
-    };
-    let story_assets = world.resource::<Assets<StoryJson>>();
-    let Some(story_json) = story_assets.get(story_handle) else {
-        return;
-    };
+    for (story_id, story_handle) in ready {
+        let ink_project = if story_id == InkStoryId::DEFAULT {
+            world.get_resource::<InkStory>()
+        } else {
+            world
+                .get_resource::<InkStoryRegistry>()
+                .and_then(|registry| registry.get(story_id))
+        };
+        let Some(ink_project) = ink_project else {
+            continue;
+        };
+        let mut initial_state = ink_project.state().cloned();
+        let seed = ink_project.seed();
+        let allow_fallbacks = ink_project.allows_external_fallbacks();
+        let started = ink_project.load_progress().is_ready();
+
+        let story_assets = world.resource::<Assets<StoryJson>>();
+        let Some(story_json) = story_assets.get(&story_handle) else {
+            continue;
+        };
+        let story_text = story_json.text.clone();
+        let story_hash = hash_story(&story_text);
+        let metadata = story_json.metadata().clone();
+        let lists = metadata.lists.clone();
+
+        let mut bindings = world.get_non_send_resource::<InkBindingMap>().expect(
+            "Failed to get binding definitions, did you forget to initialize the bevy_bladeink plugin?",
+        ).clone();
+        // story specific bindings take precedence over global ones
+        if let Some(story_bindings) = world
+            .get_non_send_resource::<InkStoryBindingMap>()
+            .and_then(|binding_defs| binding_defs.get(&story_id))
+        {
+            bindings.extend(
+                story_bindings
+                    .iter()
+                    .map(|(name, binding)| (name.clone(), binding.clone())),
+            );
+        }
+        let bound = InkBoundFunctions::new(&bindings, allow_fallbacks);
+
+        world.resource_mut::<InkAssetReady>().0.remove(&story_id);
+
+        let report = validate_bindings(story_id, &metadata, &bindings, allow_fallbacks);
+        let strict = world.resource::<InkBindingValidation>().strict;
+        world.trigger(report.clone());
+        if strict && report.has_errors() {
+            let error = format!(
+                "missing bindings: {:?}, arity mismatches: {:?}",
+                report.missing, report.arity_mismatches
+            );
+            fail_story_load(world, story_id, InkLoadStage::Bindings, error);
+            continue;
+        }
 
-    let binding_defs = world.get_non_send_resource::<InkBindingMap>().expect(
-        "Failed to get binding definitions, did you forget to initialize the bevy_bladeink plugin?",
-    );
+        // a reloaded story carries on from its current state instead
+        if started {
+            initial_state = None;
+        }
+        if let Some(state) = &mut initial_state {
+            let migration = world
+                .get_resource::<InkStateMigrations>()
+                .and_then(|migrations| migrations.get(story_id))
+                .cloned();
+            if let Err(err) = migrate_state(state, Some(story_hash), migration) {
+                fail_story_load(world, story_id, InkLoadStage::StateRestore, err.to_string());
+                continue;
+            }
+        }
 
-    let Some(story) = create_story(&story_json.text, binding_defs, ink_project.state()) else {
+        if let Some(mut ink_story) = ink_story_mut(world, story_id)
+            && !ink_story.load_progress().is_ready()
+        {
+            ink_story.set_load_progress(InkLoadProgress::CreatingStory);
+        }
+        let variable_sender = world
+            .get_resource::<CrossbeamEventSender<VariableUpdated>>()
+            .cloned();
+
+        run_ink(world, move |stories| {
+            let mut effects = InkEffects::default();
+            // a story which is already loaded is being hot reloaded, so it
+            // carries on from the state of the previous one
+            let reloading = stories.contains(story_id);
+            let existing_state = if reloading {
+                match stories.save_state(story_id) {
+                    Some(Ok(state)) => Some(state.serialized_state),
+                    Some(Err(err)) => {
+                        warn!("Failed to save state of reloaded story: {err}");
+                        None
+                    }
+                    None => None,
+                }
+            } else {
+                initial_state
+                    .as_ref()
+                    .map(|state| state.serialized_state.clone())
+            };
+            let (story, binding_events) = collect_binding_events(|| {
+                create_story(
+                    &story_text,
+                    &bindings,
+                    existing_state.as_deref(),
+                    seed,
+                    allow_fallbacks,
+                )
+            });
+            effects.append(binding_events);
+            let (story, restored) = match story {
+                Ok(created) => created,
+                Err(err) => {
+                    let error = err.to_string();
+                    effects.push(move |world| {
+                        fail_story_load(world, story_id, InkLoadStage::StoryConstruction, error);
+                    });
+                    return effects;
+                }
+            };
+            let restored = match restored {
+                Ok(restored) => restored,
+                Err(err) if reloading => {
+                    warn!("Failed to restore state of reloaded story: {err}");
+                    false
+                }
+                Err(err) => {
+                    let error = err.to_string();
+                    effects.push(move |world| {
+                        fail_story_load(world, story_id, InkLoadStage::StateRestore, error);
+                    });
+                    return effects;
+                }
+            };
+
+            stories.insert(story_id, story, bound, story_hash, lists);
+            effects.push(move |world| {
+                if let Some(mut ink_story) = ink_story_mut(world, story_id) {
+                    ink_story.set_load_progress(InkLoadProgress::Ready);
+                }
+            });
+            if !reloading {
+                if let (Some(state), Some(sender)) = (initial_state, variable_sender) {
+                    let names = state.tracked_variables.into_keys().collect();
+                    observe_variables(stories, story_id, names, sender, &mut effects);
+                }
+                effects.trigger(StoryReady { story: story_id });
+                return effects;
+            }
+
+            if !restored {
+                stories.clear_speakers(story_id);
+                stories.clear_suspension(story_id);
+            }
+            if let Some(sender) = variable_sender {
+                let names = stories.tracked(story_id);
+                observe_variables(stories, story_id, names, sender, &mut effects);
+            }
+            effects.trigger(StoryReloaded {
+                story: story_id,
+                restored,
+            });
+            effects
+        });
+    }
+}
+
+/// Reports that a story failed to load. A story which is already running keeps
+/// its progress, as the previous version of it keeps running.
+pub(crate) fn fail_story_load(
+    world: &mut World,
+    id: InkStoryId,
+    stage: InkLoadStage,
+    error: String,
+) {
+    let Some(mut ink_story) = ink_story_mut(world, id) else {
         return;
     };
+    let path = ink_story.asset_path().to_string();
+    error!("Failed to load story '{path}' ({stage:?}): {error}");
+    if !ink_story.load_progress().is_ready() {
+        ink_story.set_load_progress(InkLoadProgress::Failed {
+            stage,
+            error: error.clone(),
+        });
+    }
+    world.trigger(StoryLoadFailed {
+        story: id,
+        path,
+        stage,
+        error,
+    });
+}
+
+pub(crate) fn ink_story(world: &World, id: InkStoryId) -> Option<&InkStory> {
+    if id == InkStoryId::DEFAULT {
+        world.get_resource::<InkStory>()
+    } else {
+        world.get_resource::<InkStoryRegistry>()?.get(id)
+    }
+}
+
+pub(crate) fn ink_story_mut(world: &mut World, id: InkStoryId) -> Option<Mut<'_, InkStory>> {
+    if id == InkStoryId::DEFAULT {
+        world.get_resource_mut::<InkStory>()
+    } else {
+        world
+            .get_resource_mut::<InkStoryRegistry>()?
+            .filter_map_unchanged(|registry| registry.get_mut(id))
+    }
+}
 
-    world.insert_non_send_resource(story);
-    world.remove_resource::<InkAssetReady>();
-    world.trigger(StoryReady);
+/// Observes the variables tracked in the previous story, or in the state the
+/// story started from, updating [`InkVariables`] with their current values.
+fn observe_variables(
+    stories: &mut InkStories,
+    id: InkStoryId,
+    names: Vec<String>,
+    sender: CrossbeamEventSender<VariableUpdated>,
+    effects: &mut InkEffects,
+) {
+    let mut values = Vec::new();
+    for name in names {
+        match observe_variable(stories, id, &name, sender.clone()) {
+            Ok(Some(value)) => values.push((name, value)),
+            Ok(None) => {}
+            Err(err) => warn!("Failed to track variable '{name}' of story {id:?}: {err}"),
+        }
+    }
+    effects.push(move |world| {
+        let Some(mut ink_variables) = world.get_resource_mut::<InkVariables>() else {
+            return;
+        };
+        for (name, value) in values {
+            ink_variables.update_from_story(id, name, value);
+        }
+    });
 }
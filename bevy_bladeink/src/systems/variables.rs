@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+use crate::{commands::SetVariableCommand, resources::InkVariables};
+
+/// Writes the tracked variables changed in Bevy back to their story. Values
+/// reported by the stories are already synced, so they don't bounce back.
+pub(crate) fn push_variable_changes(
+    mut ink_variables: ResMut<InkVariables>,
+    mut commands: Commands,
+) {
+    for (story, name, value) in ink_variables.bypass_change_detection().take_changes() {
+        commands.queue(SetVariableCommand::new(story, name, value));
+    }
+}
@@ -15,7 +15,7 @@ pub(crate) fn on_begin_sequence(
 ) {
     #[cfg(feature = "debug_log")]
     {
-        info!("Sequence began: {} - spawning UI", _seq.0);
+        info!("Sequence began: {} - spawning UI", _seq.path);
     }
     for existing in q_existing {
         warn!("Found existing dialogue root ({existing:?}), despawning...");
@@ -1,4 +1,4 @@
-//! Integration tests for the `InkBinding` derive macro.
+//! Integration tests for the `InkBinding` and `InkGlobals` derive macros.
 //!
 //! These tests verify that derived implementations match the expected behavior
 //! of manual implementations.
@@ -313,3 +313,79 @@ fn test_derived_all_types_validates_each_field() {
     let result = DerivedAllTypes::try_parse_event(&args);
     assert!(matches!(result, Err(InkBindingError::InvalidArguments)));
 }
+
+// ============================================================================
+// Binding options
+// ============================================================================
+
+#[derive(Event, Clone, Debug, PartialEq, InkBinding)]
+#[ink(lookahead_safe, allow_fallback)]
+struct DerivedWithOptions(i32);
+
+#[derive(Event, Clone, Debug, PartialEq, InkBinding)]
+#[ink(lookahead_safe)]
+struct DerivedLookaheadSafe;
+
+fn options<T: InkBindingDefinition>() -> (bool, bool) {
+    (T::LOOKAHEAD_SAFE, T::ALLOW_FALLBACK)
+}
+
+#[test]
+fn test_derived_options_default_to_false() {
+    assert_eq!(options::<DerivedNoArgs>(), (false, false));
+}
+
+#[test]
+fn test_derived_options_from_attribute() {
+    assert_eq!(options::<DerivedWithOptions>(), (true, true));
+    assert_eq!(options::<DerivedLookaheadSafe>(), (true, false));
+
+    let result = DerivedWithOptions::try_parse_event(&[ValueType::from(3)]);
+    assert_eq!(result.unwrap(), DerivedWithOptions(3));
+}
+
+#[test]
+fn test_derived_arity_is_field_count() {
+    assert_eq!(DerivedNoArgs::ARITY, Some(0));
+    assert_eq!(DerivedSingleString::ARITY, Some(1));
+    assert_eq!(DerivedWithOptions::ARITY, Some(1));
+}
+
+// ============================================================================
+// Ink globals
+// ============================================================================
+
+#[derive(Resource, Default, InkGlobals)]
+struct DerivedGlobals {
+    forceful: i32,
+    #[ink(name = "teacup")]
+    has_teacup: bool,
+    name: String,
+    items: InkList,
+}
+
+#[test]
+fn test_derived_globals_variables() {
+    assert_eq!(
+        DerivedGlobals::variables(),
+        vec![
+            ("forceful", InkValueKind::Int),
+            ("teacup", InkValueKind::Bool),
+            ("name", InkValueKind::String),
+            ("items", InkValueKind::List),
+        ]
+    );
+}
+
+#[test]
+fn test_derived_globals_set_variable() {
+    let mut globals = DerivedGlobals::default();
+    assert!(globals.set_variable("forceful", &InkValue::Int(2)));
+    assert!(!globals.set_variable("forceful", &InkValue::Int(2)));
+    assert!(globals.set_variable("teacup", &InkValue::Bool(true)));
+    assert!(!globals.set_variable("has_teacup", &InkValue::Bool(false)));
+    assert!(!globals.set_variable("name", &InkValue::Int(1)));
+    assert_eq!(globals.forceful, 2);
+    assert!(globals.has_teacup);
+    assert!(globals.name.is_empty());
+}
@@ -0,0 +1,1256 @@
+//! Integration tests running stories through the full plugin.
+
+use bevy::prelude::*;
+use bevy_bladeink::{
+    assets::{InkDiagnostic, StoryJson, StubInkCompiler},
+    prelude::*,
+};
+use bladeink::value_type::ValueType;
+
+const SIDE_STORY: InkStoryId = InkStoryId(1);
+
+#[derive(Event, Clone, InkBinding)]
+struct SetTextColor(String);
+
+#[derive(Event, Clone, InkBinding)]
+struct CountItems(String);
+
+#[derive(Event, Clone, InkBinding)]
+struct WalkTo(String);
+
+#[derive(Resource)]
+struct Inventory {
+    keys: i32,
+}
+
+fn count_items(In(CountItems(item)): In<CountItems>, inventory: Res<Inventory>) -> i32 {
+    if item == "key" { inventory.keys } else { 0 }
+}
+
+#[derive(Resource, Default)]
+struct Received {
+    ready: Vec<InkStoryId>,
+    reloaded: Vec<StoryReloaded>,
+    reports: Vec<InkBindingReport>,
+    failures: Vec<StoryLoadFailed>,
+    lines: Vec<DeliverLine>,
+    suspended: Vec<SequenceSuspended>,
+    walks: Vec<String>,
+    states: Vec<InkStateUpdate>,
+    colors: Vec<String>,
+    order: Vec<&'static str>,
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), InkPlugin))
+        .bind_ink_function::<SetTextColor>("set_text_color")
+        .init_resource::<Received>()
+        .add_observer(|ready: On<StoryReady>, mut received: ResMut<Received>| {
+            received.ready.push(ready.story);
+        })
+        .add_observer(
+            |reloaded: On<StoryReloaded>, mut received: ResMut<Received>| {
+                received.reloaded.push(reloaded.clone());
+            },
+        )
+        .add_observer(
+            |report: On<InkBindingReport>, mut received: ResMut<Received>| {
+                received.reports.push(report.clone());
+            },
+        )
+        .add_observer(
+            |failed: On<StoryLoadFailed>, mut received: ResMut<Received>| {
+                received.failures.push(failed.clone());
+            },
+        )
+        .add_observer(|color: On<SetTextColor>, mut received: ResMut<Received>| {
+            received.colors.push(color.0.clone());
+            received.order.push("color");
+        })
+        .add_observer(|line: On<DeliverLine>, mut received: ResMut<Received>| {
+            received.lines.push(line.clone());
+            received.order.push("line");
+        })
+        .add_observer(|walk: On<WalkTo>, mut received: ResMut<Received>| {
+            received.walks.push(walk.0.clone());
+        })
+        .add_observer(
+            |suspended: On<SequenceSuspended>, mut received: ResMut<Received>| {
+                received.suspended.push(suspended.clone());
+            },
+        )
+        .add_observer(
+            |state: On<InkStateUpdate>, mut received: ResMut<Received>| {
+                received.states.push(state.clone());
+            },
+        );
+    app
+}
+
+fn update_until(app: &mut App, done: impl Fn(&Received) -> bool) {
+    for _ in 0..1000 {
+        app.update();
+        if done(app.world().resource::<Received>()) {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    panic!("condition not reached");
+}
+
+#[test]
+fn test_multiple_stories_run_independently() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/bindings.ink.json"))
+        .add_ink_story(SIDE_STORY, InkStory::new("ink/TheIntercept.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 2);
+
+    let mut commands = app.world_mut().commands();
+    commands.ink_begin_sequence("start").ink_continue_sequence();
+    commands
+        .ink_story(SIDE_STORY)
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].story, InkStoryId::DEFAULT);
+    assert_eq!(received.lines[0].text, "the text is red!\n");
+    assert_eq!(received.lines[1].story, SIDE_STORY);
+    assert_ne!(received.lines[1].text, "the text is red!\n");
+}
+
+#[test]
+fn test_flows_keep_their_position() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/bindings.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    let mut commands = app.world_mut().commands();
+    commands
+        .ink_begin_sequence_in_flow("alice", "start")
+        .ink_continue_sequence()
+        .ink_begin_sequence_in_flow("bob", "start")
+        .ink_continue_sequence()
+        .ink_switch_flow("alice")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| {
+        received.lines.len() == 3 && received.states.len() == 3
+    });
+
+    let received = app.world().resource::<Received>();
+    let lines: Vec<_> = received
+        .lines
+        .iter()
+        .map(|line| (line.flow.as_deref(), line.text.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (Some("alice"), "the text is red!\n"),
+            (Some("bob"), "the text is red!\n"),
+            (Some("alice"), "now the text is green!\n"),
+        ]
+    );
+
+    let flows = received.states.last().unwrap().state.flows();
+    assert_eq!(flows.current(), Some("alice"));
+    assert!(flows.contains("alice"));
+    assert!(flows.contains("bob"));
+}
+
+#[derive(Resource, Default)]
+struct Spoken(Vec<(Entity, String)>);
+
+#[test]
+fn test_ink_path_targets_speaker() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/bindings.ink.json"))
+        .init_resource::<Spoken>();
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    let record = |line: On<InkEntityEvent<DeliverLine>>, mut spoken: ResMut<Spoken>| {
+        spoken.0.push((line.entity, line.text.clone()));
+    };
+    let npc = app
+        .world_mut()
+        .spawn(InkPath::new("start").in_flow("npc"))
+        .observe(record)
+        .id();
+    let bystander = app
+        .world_mut()
+        .spawn(InkPath::new("start"))
+        .observe(record)
+        .id();
+
+    let mut commands = app.world_mut().commands();
+    commands.entity(npc).ink_begin();
+    commands.ink_continue_sequence();
+    commands.ink_begin_sequence("start").ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let spoken = &app.world().resource::<Spoken>().0;
+    assert_eq!(spoken, &vec![(npc, "the text is red!\n".to_string())]);
+    assert!(spoken.iter().all(|(entity, _)| *entity != bystander));
+}
+
+#[test]
+fn test_system_binding_reads_world() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/world.ink.json"))
+        .insert_resource(Inventory { keys: 3 })
+        .bind_ink_system("count_items", count_items);
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You have 3 keys.\n");
+}
+
+fn count_items_and_visit(In(CountItems(_)): In<CountItems>, mut commands: Commands) -> i32 {
+    commands.ink_set_variable("visits".to_string(), 10);
+    2
+}
+
+#[test]
+fn test_system_binding_issues_ink_commands() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/suspense.ink.json"))
+        .bind_ink_system("count_items", count_items_and_visit)
+        .bind_ink_fn("walk_to", |_| Some(InkValue::Int(12)));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_track_variable("visits")
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(
+        received.lines[0].text,
+        "You pick up 2 keys and walk 12 steps.\n"
+    );
+    // the command runs once the line is done, overriding its `visits += 1`
+    assert_eq!(
+        app.world().resource::<InkVariables>().get_int("visits"),
+        Some(10)
+    );
+}
+
+#[test]
+fn test_blocking_binding_suspends_story() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/cutscene.ink.json"))
+        .bind_ink_blocking_function::<WalkTo>("walk_to");
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence()
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.suspended.len() == 1);
+
+    // the story refuses to carry on until it is resumed
+    app.world_mut().commands().ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.suspended.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines.len(), 1);
+    assert_eq!(received.suspended[0].function, "walk_to");
+    let token = received.suspended[0].token;
+    assert_eq!(received.suspended[1].token, token);
+
+    app.world_mut().commands().ink_resume_with(token, 12);
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[1].text, "12 steps later, you arrive.\n");
+    assert_eq!(received.walks, vec!["door".to_string()]);
+}
+
+#[derive(Resource, Default)]
+struct ItemCounts(u32);
+
+fn count_items_once(
+    In(CountItems(_)): In<CountItems>,
+    inventory: Res<Inventory>,
+    mut counts: ResMut<ItemCounts>,
+) -> i32 {
+    counts.0 += 1;
+    inventory.keys
+}
+
+#[derive(Resource, Default)]
+struct VisitChanges(Vec<(Option<InkValue>, InkValue)>);
+
+#[test]
+fn test_suspended_line_runs_its_effects_once() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/suspense.ink.json"))
+        .insert_resource(Inventory { keys: 3 })
+        .init_resource::<ItemCounts>()
+        .init_resource::<VisitChanges>()
+        .bind_ink_system("count_items", count_items_once)
+        .bind_ink_blocking_function::<WalkTo>("walk_to")
+        .add_observer(
+            |changed: On<InkVariableChanged>, mut changes: ResMut<VisitChanges>| {
+                changes.0.push((changed.old.clone(), changed.new.clone()));
+            },
+        );
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_track_variable("visits")
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.suspended.len() == 1);
+
+    // the line is rolled back until resumed
+    assert_eq!(app.world().resource::<ItemCounts>().0, 1);
+    assert!(app.world().resource::<VisitChanges>().0.is_empty());
+
+    let token = app.world().resource::<Received>().suspended[0].token;
+    app.world_mut().commands().ink_resume_with(token, 12);
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(
+        received.lines[0].text,
+        "You pick up 3 keys and walk 12 steps.\n"
+    );
+    assert_eq!(app.world().resource::<ItemCounts>().0, 1);
+    assert_eq!(
+        app.world().resource::<VisitChanges>().0,
+        vec![(Some(InkValue::Int(0)), InkValue::Int(1))]
+    );
+}
+
+#[test]
+fn test_binding_events_precede_their_line() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/bindings.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence()
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.colors, vec!["#FF0000", "#00FF00"]);
+    assert_eq!(received.order, vec!["color", "line", "color", "line"]);
+}
+
+#[test]
+fn test_bindings_apply_to_loaded_stories() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/world.ink.json"))
+        .insert_resource(Inventory { keys: 3 });
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.bind_ink_system("count_items", count_items)
+        .bind_ink_system_for(
+            InkStoryId::DEFAULT,
+            "count_items",
+            |In(_): In<CountItems>| 7,
+        );
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    // the story falls back to the binding registered for every story
+    app.unbind_ink_function_for(InkStoryId::DEFAULT, "count_items");
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You have 7 keys.\n");
+    assert_eq!(received.lines[1].text, "You have 3 keys.\n");
+}
+
+#[test]
+fn test_bindings_change_through_commands() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/world.ink.json"))
+        .bind_ink_fn("count_items", |_: &[InkValue]| Some(InkValue::Int(3)));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_bind_fn("count_items", |_: &[InkValue]| Some(InkValue::Int(7)))
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_unbind_function("count_items")
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You have 7 keys.\n");
+    assert_eq!(received.lines[1].text, "You have 3 keys.\n");
+}
+
+#[test]
+fn test_binding_report_lists_missing_bindings() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/world.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.reports.len(), 1);
+    assert_eq!(received.reports[0].missing, vec!["count_items"]);
+    assert_eq!(received.reports[0].unused, vec!["set_text_color"]);
+    assert!(received.reports[0].has_errors());
+}
+
+#[test]
+fn test_strict_validation_refuses_story() {
+    #[derive(Event, Clone, InkBinding)]
+    struct CountAll;
+
+    let mut app = app();
+    app.insert_resource(InkBindingValidation { strict: true })
+        .insert_resource(InkStory::new("ink/world.ink.json"))
+        .bind_ink_function::<CountAll>("count_items");
+
+    update_until(&mut app, |received| received.reports.len() == 1);
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(
+        received.reports[0].arity_mismatches,
+        vec![InkArityMismatch {
+            name: "count_items".to_string(),
+            expected: 0,
+            called_with: 1,
+        }]
+    );
+    assert!(received.ready.is_empty());
+}
+
+#[test]
+fn test_strict_validation_accepts_story_level_fallbacks() {
+    // the shared app binds `set_text_color`, so this one has no bindings
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), InkPlugin))
+        .init_resource::<Received>()
+        .add_observer(|ready: On<StoryReady>, mut received: ResMut<Received>| {
+            received.ready.push(ready.story);
+        })
+        .add_observer(
+            |report: On<InkBindingReport>, mut received: ResMut<Received>| {
+                received.reports.push(report.clone());
+            },
+        )
+        .insert_resource(InkBindingValidation { strict: true })
+        .insert_resource(InkStory::new("ink/bindings.ink.json").allow_external_fallbacks(true));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert!(received.reports[0].missing.is_empty());
+    assert!(!received.reports[0].has_errors());
+}
+
+#[test]
+fn test_closure_binding_returns_value() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/world.ink.json"))
+        .bind_ink_fn("count_items", |args: &[InkValue]| match args {
+            [InkValue::String(item)] if item == "key" => Some(InkValue::Int(5)),
+            _ => Some(InkValue::Int(0)),
+        });
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You have 5 keys.\n");
+}
+
+#[test]
+fn test_hot_reload_keeps_position() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/bindings.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let mut assets = app.world_mut().resource_mut::<Assets<StoryJson>>();
+    let id = assets.ids().next().unwrap();
+    let text = std::fs::read_to_string("assets/ink/bindings.ink.json").unwrap();
+    let text = text.trim_start_matches('\u{feff}').to_string();
+    assets.insert(id, StoryJson::new(text)).unwrap();
+    update_until(&mut app, |received| received.reloaded.len() == 1);
+
+    app.world_mut().commands().ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.ready.len(), 1);
+    assert!(received.reloaded[0].restored);
+    assert_eq!(received.lines[1].text, "now the text is green!\n");
+}
+
+#[test]
+fn test_ink_source_compiles_with_includes() {
+    let json = std::fs::read_to_string("assets/ink/bindings.ink.json").unwrap();
+    let compiler = StubInkCompiler::new().with_story("ink/chapters.ink", json);
+    let mut app = app();
+    app.use_ink_compiler(compiler.clone())
+        .insert_resource(InkStory::new("ink/chapters.ink"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "the text is red!\n");
+    let sources = compiler.sources();
+    assert_eq!(sources.len(), 1);
+    assert!(sources[0].text.contains("INCLUDE bindings.ink"));
+    assert!(sources[0].includes["bindings.ink"].contains("EXTERNAL set_text_color"));
+}
+
+#[test]
+fn test_load_progress_reaches_ready() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/world.ink.json"));
+    assert_eq!(
+        app.world().resource::<InkStory>().load_progress(),
+        &InkLoadProgress::NotStarted
+    );
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+
+    let progress = app.world().resource::<InkStory>().load_progress();
+    assert!(progress.is_ready());
+    assert_eq!(progress.fraction(), 1.0);
+}
+
+#[test]
+fn test_missing_asset_fails_load() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/missing.ink.json"));
+
+    update_until(&mut app, |received| received.failures.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.failures[0].path, "ink/missing.ink.json");
+    assert_eq!(received.failures[0].stage, InkLoadStage::AssetIo);
+    let progress = app.world().resource::<InkStory>().load_progress();
+    assert!(matches!(
+        progress,
+        InkLoadProgress::Failed {
+            stage: InkLoadStage::AssetIo,
+            ..
+        }
+    ));
+    assert!(progress.error().is_some());
+}
+
+#[test]
+fn test_load_failures_report_their_stage() {
+    let compiler = StubInkCompiler::new()
+        .with_story("ink/chapters.ink", "not json")
+        .with_errors(
+            "ink/world.ink",
+            vec![InkDiagnostic::error("Unexpected token")],
+        );
+    let mut app = app();
+    app.use_ink_compiler(compiler)
+        .insert_resource(InkStory::new("ink/chapters.ink"))
+        .add_ink_story(SIDE_STORY, InkStory::new("ink/world.ink"));
+
+    update_until(&mut app, |received| received.failures.len() == 2);
+
+    let received = app.world().resource::<Received>();
+    let stage = |story| {
+        received
+            .failures
+            .iter()
+            .find(|failed| failed.story == story)
+            .map(|failed| failed.stage)
+    };
+    assert_eq!(stage(InkStoryId::DEFAULT), Some(InkLoadStage::JsonParse));
+    assert_eq!(stage(SIDE_STORY), Some(InkLoadStage::Compile));
+    assert!(received.ready.is_empty());
+}
+
+#[test]
+fn test_list_variables_round_trip() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/inventory.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut().commands().ink_track_variable("items");
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let items = app
+        .world()
+        .resource::<InkVariables>()
+        .get_list("items")
+        .cloned()
+        .unwrap();
+    assert!(items.contains_item("key"));
+    assert_eq!(items.all_items().len(), 3);
+
+    let sword = items.inverse().remove("sword").unwrap();
+    let mut updated = items.clone();
+    updated.insert(sword);
+    app.world_mut()
+        .commands()
+        .ink_set_variable("items".to_string(), updated.clone())
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You carry key, sword.\n");
+    assert_eq!(
+        app.world().resource::<InkVariables>().get_list("items"),
+        Some(&updated)
+    );
+}
+
+#[test]
+fn test_lists_built_in_bevy_know_their_origins() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/inventory.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    let map = InkList::from_items([InkListItem::new("Inventory", "map", 2)]);
+    assert!(map.all_items().is_empty());
+    app.world_mut()
+        .commands()
+        .ink_track_variable("items")
+        .ink_set_variable("items".to_string(), map)
+        .ink_begin_sequence("stock")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(
+        received.lines[0].text,
+        "You could carry key, map, sword, but miss key, sword.\n"
+    );
+    let items = app.world().resource::<InkVariables>().get_list("items");
+    assert_eq!(items.map(|items| items.all_items().len()), Some(3));
+}
+
+#[test]
+fn test_rejected_variable_reverts_to_story_value() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/inventory.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut().commands().ink_track_variable("items");
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    // the story has no `shield` in its Inventory list, so it keeps its value
+    let shield = InkList::from_items([InkListItem::new("Inventory", "shield", 4)]);
+    assert!(
+        app.world_mut()
+            .resource_mut::<InkVariables>()
+            .set("items", shield)
+    );
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let items = app
+        .world()
+        .resource::<InkVariables>()
+        .get_list("items")
+        .cloned()
+        .unwrap();
+    assert!(items.contains_item("key"));
+    assert!(!items.contains_item("shield"));
+}
+
+#[test]
+fn test_divert_target_variables_round_trip() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/directions.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut().commands().ink_track_variable("next");
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let variables = app.world().resource::<InkVariables>();
+    assert_eq!(
+        variables.get_divert_target("next").map(String::as_str),
+        Some("market")
+    );
+
+    app.world_mut()
+        .commands()
+        .ink_set_variable(
+            "next".to_string(),
+            InkValue::DivertTarget("docks".to_string()),
+        )
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You arrive at the docks.\n");
+}
+
+#[derive(Resource, Default, InkGlobals)]
+struct Intercept {
+    forceful: i32,
+    #[ink(name = "teacup")]
+    has_teacup: bool,
+}
+
+#[test]
+fn test_ink_globals_mirror_story_variables() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/TheIntercept.ink.json"))
+        .init_ink_globals::<Intercept>();
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(
+        app.world().resource::<InkVariables>().get_bool("teacup"),
+        Some(false)
+    );
+
+    app.world_mut()
+        .commands()
+        .ink_set_variable("forceful".to_string(), 2)
+        .ink_set_variable("teacup".to_string(), true);
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let intercept = app.world().resource::<Intercept>();
+    assert_eq!(intercept.forceful, 2);
+    assert!(intercept.has_teacup);
+}
+
+#[test]
+fn test_variables_set_in_bevy_are_written_back() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/inventory.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut().commands().ink_track_variable("items");
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let mut variables = app.world_mut().resource_mut::<InkVariables>();
+    let mut items = variables.get_list("items").cloned().unwrap();
+    items.insert(items.inverse().remove("map").unwrap());
+    assert!(variables.set("items", items));
+    for _ in 0..10 {
+        app.update();
+    }
+    // the story echoing the change doesn't write it again
+    assert_eq!(app.world().resource::<Received>().states.len(), 1);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| received.lines.len() == 1);
+
+    let received = app.world().resource::<Received>();
+    assert_eq!(received.lines[0].text, "You carry key, map.\n");
+}
+
+#[derive(Resource, Default)]
+struct ForcefulChanges(Vec<(Option<i32>, i32)>);
+
+fn on_forceful(In(change): In<InkVariableChange<i32>>, mut changes: ResMut<ForcefulChanges>) {
+    changes.0.push((change.old, change.new));
+}
+
+#[test]
+fn test_observed_variable_reports_changes() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/TheIntercept.ink.json"))
+        .init_resource::<ForcefulChanges>()
+        .observe_ink_variable("forceful", on_forceful);
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(app.world().resource::<ForcefulChanges>().0.is_empty());
+
+    app.world_mut()
+        .commands()
+        .ink_set_variable("forceful".to_string(), 2)
+        .ink_set_variable("forceful".to_string(), 3);
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    assert_eq!(
+        app.world().resource::<ForcefulChanges>().0,
+        vec![(Some(0), 2), (Some(2), 3)]
+    );
+}
+
+#[test]
+fn test_track_variables_in_bulk() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/TheIntercept.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_track_variables_matching("*component*");
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let variables = app.world().resource::<InkVariables>();
+    assert_eq!(variables.get_bool("gotcomponent"), Some(false));
+    assert_eq!(variables.get_bool("throwncomponentaway"), Some(false));
+    assert_eq!(variables.get_int("forceful"), None);
+
+    app.world_mut().commands().ink_track_all_variables();
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(
+        app.world().resource::<InkVariables>().get_int("forceful"),
+        Some(0)
+    );
+
+    app.world_mut()
+        .commands()
+        .ink_untrack_variable("forceful")
+        .ink_set_variable("forceful".to_string(), 2)
+        .ink_set_variable("evasive".to_string(), 1);
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let variables = app.world().resource::<InkVariables>();
+    assert_eq!(variables.get_int("forceful"), None);
+    assert_eq!(variables.get_int("evasive"), Some(1));
+}
+
+#[derive(Resource, Default)]
+struct StateFailures(Vec<InkStateError>);
+
+#[test]
+fn test_state_of_another_story_version_needs_migration() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/directions.ink.json"))
+        .init_resource::<StateFailures>()
+        .add_observer(
+            |failed: On<StateLoadFailed>, mut failures: ResMut<StateFailures>| {
+                failures.0.push(failed.error.clone());
+            },
+        );
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| !received.states.is_empty());
+
+    let state = app.world().resource::<Received>().states[0].state.clone();
+    let current = state.story_hash().unwrap();
+    let mut json = serde_json::to_value(&state).unwrap();
+    json["story_hash"] = 1.into();
+    let old_state: InkState = serde_json::from_value(json).unwrap();
+
+    app.world_mut().commands().ink_load_state(old_state.clone());
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(
+        app.world().resource::<StateFailures>().0,
+        vec![InkStateError::StoryMismatch { saved: 1, current }]
+    );
+
+    app.add_ink_state_migration(|state| {
+        assert_eq!(state.story_hash(), Some(1));
+        Ok(())
+    });
+    app.world_mut().commands().ink_load_state(old_state);
+    app.world_mut().flush();
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<StateFailures>().0.len(), 1);
+}
+
+#[derive(Resource, Default)]
+struct SlotEvents {
+    saved: Vec<InkSlotMetadata>,
+    loaded: Vec<InkSlotMetadata>,
+    failed: Vec<InkSaveError>,
+}
+
+fn update_slots_until(app: &mut App, done: impl Fn(&SlotEvents) -> bool) {
+    for _ in 0..1000 {
+        app.update();
+        if done(app.world().resource::<SlotEvents>()) {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    panic!("condition not reached");
+}
+
+#[test]
+fn test_save_slots_round_trip() {
+    let mut app = app();
+    app.add_plugins(InkSavePlugin::memory())
+        .insert_resource(InkStory::new("ink/directions.ink.json"))
+        .init_resource::<SlotEvents>()
+        .add_observer(|saved: On<InkSlotSaved>, mut events: ResMut<SlotEvents>| {
+            events.saved.push(saved.metadata.clone());
+        })
+        .add_observer(
+            |loaded: On<InkSlotLoaded>, mut events: ResMut<SlotEvents>| {
+                events.loaded.push(loaded.metadata.clone());
+            },
+        )
+        .add_observer(
+            |failed: On<InkSlotFailed>, mut events: ResMut<SlotEvents>| {
+                events.failed.push(failed.error.clone());
+            },
+        );
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence()
+        .ink_save_to_slot("quicksave");
+    app.world_mut().flush();
+    update_slots_until(&mut app, |events| !events.saved.is_empty());
+
+    let events = app.world().resource::<SlotEvents>();
+    assert_eq!(events.saved.len(), 1);
+    assert_eq!(events.saved[0].slot, "quicksave");
+    assert_eq!(
+        events.saved[0].last_line.as_deref(),
+        Some("You arrive at the market.")
+    );
+    let slots = app.world().resource::<InkSaves>().slots().unwrap();
+    assert_eq!(slots, events.saved);
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("docks")
+        .ink_continue_sequence()
+        .ink_load_from_slot("quicksave")
+        .ink_delete_slot("quicksave")
+        .ink_load_from_slot("quicksave");
+    app.world_mut().flush();
+    update_slots_until(&mut app, |events| !events.failed.is_empty());
+
+    let events = app.world().resource::<SlotEvents>();
+    assert_eq!(events.loaded, events.saved);
+    assert_eq!(
+        events.failed,
+        vec![InkSaveError::NotFound("quicksave".to_string())]
+    );
+    let playthrough = app.world().resource::<InkPlaythrough>();
+    assert_eq!(
+        playthrough
+            .story(InkStoryId::DEFAULT)
+            .and_then(|playthrough| playthrough.last_line.as_deref()),
+        Some("You arrive at the market.")
+    );
+    assert!(
+        app.world()
+            .resource::<InkSaves>()
+            .slots()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[derive(Resource, Default)]
+struct CountChanges(Vec<(Option<InkValue>, InkValue)>);
+
+#[test]
+fn test_loading_slot_refreshes_tracked_variables() {
+    let mut app = app();
+    app.add_plugins(InkSavePlugin::memory())
+        .insert_resource(InkStory::new("ink/bindings.ink.json"))
+        .add_ink_story(SIDE_STORY, InkStory::new("ink/bindings.ink.json"))
+        .init_resource::<SlotEvents>()
+        .init_resource::<CountChanges>()
+        .add_observer(|saved: On<InkSlotSaved>, mut events: ResMut<SlotEvents>| {
+            events.saved.push(saved.metadata.clone());
+        })
+        .add_observer(
+            |loaded: On<InkSlotLoaded>, mut events: ResMut<SlotEvents>| {
+                events.loaded.push(loaded.metadata.clone());
+            },
+        )
+        .add_observer(
+            |failed: On<InkSlotFailed>, mut events: ResMut<SlotEvents>| {
+                events.failed.push(failed.error.clone());
+            },
+        )
+        .add_observer(
+            |changed: On<InkVariableChanged>, mut changes: ResMut<CountChanges>| {
+                if changed.story == InkStoryId::DEFAULT {
+                    changes.0.push((changed.old.clone(), changed.new.clone()));
+                }
+            },
+        );
+
+    update_until(&mut app, |received| received.ready.len() == 2);
+    app.world_mut()
+        .commands()
+        .ink_track_variable("color_change_count")
+        .ink_begin_sequence("start")
+        .ink_continue_sequence()
+        .ink_save_to_slot("quicksave")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_slots_until(&mut app, |events| !events.saved.is_empty());
+    update_until(&mut app, |received| received.lines.len() == 2);
+    let count = |app: &App| {
+        app.world()
+            .resource::<InkVariables>()
+            .story(InkStoryId::DEFAULT)
+            .get_int("color_change_count")
+    };
+    assert_eq!(count(&app), Some(2));
+
+    app.world_mut().commands().ink_load_from_slot("quicksave");
+    app.world_mut()
+        .commands()
+        .ink_story(SIDE_STORY)
+        .ink_load_from_slot("quicksave");
+    app.world_mut().flush();
+    update_slots_until(&mut app, |events| {
+        !events.loaded.is_empty() && !events.failed.is_empty()
+    });
+
+    assert_eq!(count(&app), Some(1));
+    assert_eq!(
+        app.world().resource::<CountChanges>().0.last(),
+        Some(&(Some(InkValue::Int(2)), InkValue::Int(1)))
+    );
+    // the slot only holds the save of the default story
+    assert_eq!(
+        app.world().resource::<SlotEvents>().failed,
+        vec![InkSaveError::OtherStory {
+            slot: "quicksave".to_string(),
+            saved: InkStoryId::DEFAULT,
+        }]
+    );
+}
+
+#[test]
+fn test_autosaves_roll_over_their_slots() {
+    let mut app = app();
+    app.add_plugins(InkSavePlugin::memory())
+        .insert_resource(InkAutosave::new(InkAutosavePolicy::EveryLines(1)).with_slots(2))
+        .insert_resource(InkStory::new("ink/directions.ink.json"))
+        .init_resource::<SlotEvents>()
+        .add_observer(|saved: On<InkSlotSaved>, mut events: ResMut<SlotEvents>| {
+            events.saved.push(saved.metadata.clone());
+        });
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    for knot in ["start", "docks", "market"] {
+        app.world_mut()
+            .commands()
+            .ink_begin_sequence(knot)
+            .ink_continue_sequence();
+        app.world_mut().flush();
+    }
+    update_slots_until(&mut app, |events| events.saved.len() == 3);
+
+    let saved: Vec<_> = app.world().resource::<SlotEvents>().saved.clone();
+    let slots: Vec<_> = saved.iter().map(|save| save.slot.as_str()).collect();
+    assert_eq!(slots, ["autosave-0", "autosave-1", "autosave-0"]);
+    let mut stored = app.world().resource::<InkSaves>().slots().unwrap();
+    stored.sort_by(|a, b| a.slot.cmp(&b.slot));
+    assert_eq!(stored, [saved[2].clone(), saved[1].clone()]);
+    assert_eq!(
+        stored[0].last_line.as_deref(),
+        Some("You arrive at the market.")
+    );
+}
+
+#[test]
+fn test_timer_autosaves_only_after_changes() {
+    let mut app = app();
+    app.add_plugins(InkSavePlugin::memory())
+        .insert_resource(InkAutosave::new(InkAutosavePolicy::Timer(
+            std::time::Duration::from_millis(5),
+        )))
+        .insert_resource(InkStory::new("ink/directions.ink.json"))
+        .init_resource::<SlotEvents>()
+        .add_observer(|saved: On<InkSlotSaved>, mut events: ResMut<SlotEvents>| {
+            events.saved.push(saved.metadata.clone());
+        });
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    let idle = |app: &mut App| {
+        for _ in 0..20 {
+            app.update();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    };
+    idle(&mut app);
+    assert!(app.world().resource::<SlotEvents>().saved.is_empty());
+
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_slots_until(&mut app, |events| !events.saved.is_empty());
+    idle(&mut app);
+    assert_eq!(app.world().resource::<SlotEvents>().saved.len(), 1);
+}
+
+#[test]
+fn test_story_starts_from_saved_state() {
+    let mut app = app();
+    app.insert_resource(InkStory::new("ink/TheIntercept.ink.json"));
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_track_variable("forceful")
+        .ink_set_variable("forceful".to_string(), 2);
+    app.world_mut().flush();
+    update_until(&mut app, |received| !received.states.is_empty());
+    let state = app.world().resource::<Received>().states[0].state.clone();
+    assert_eq!(state.tracked_variables().get("forceful"), Some(&2.into()));
+
+    let mut app = self::app();
+    app.insert_resource(InkStory::new("ink/TheIntercept.ink.json").with_state(state));
+    update_until(&mut app, |received| received.ready.len() == 1);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(
+        app.world().resource::<InkVariables>().get_int("forceful"),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_story_seed_is_applied() {
+    let mut app = app();
+    app.insert_resource(
+        InkStory::new("ink/directions.ink.json")
+            .with_seed(1234)
+            .allow_external_fallbacks(true),
+    );
+
+    update_until(&mut app, |received| received.ready.len() == 1);
+    app.world_mut()
+        .commands()
+        .ink_begin_sequence("start")
+        .ink_continue_sequence();
+    app.world_mut().flush();
+    update_until(&mut app, |received| !received.states.is_empty());
+
+    let state = &app.world().resource::<Received>().states[0].state;
+    let state: serde_json::Value = serde_json::from_str(state.serialized_state()).unwrap();
+    assert_eq!(state["storySeed"], 1234);
+}
@@ -1,7 +1,8 @@
-//! Procedural derive macro for `InkBindingDefinition`.
+//! Procedural derive macros for `InkBindingDefinition` and `InkGlobals`.
 //!
 //! This crate provides the `#[derive(InkBinding)]` macro that automatically
-//! implements the `InkBindingDefinition` trait for structs with basic field types.
+//! implements the `InkBindingDefinition` trait for structs with basic field types,
+//! and the `#[derive(InkGlobals)]` macro mirroring ink variables into a resource.
 //!
 //! # Supported Field Types
 //!
@@ -33,6 +34,23 @@
 //! struct SimpleEvent(String);
 //! ```
 //!
+//! # Binding Options
+//!
+//! The `#[ink(...)]` attribute sets the options of the binding:
+//!
+//! - `lookahead_safe` - The binding can be called while the story looks ahead
+//! - `allow_fallback` - The story may use the ink fallback function when the
+//!   binding is not bound
+//!
+//! The number of fields is used as the arity of the binding, which is checked
+//! against the calls the story makes when it is loaded.
+//!
+//! ```ignore
+//! #[derive(Event, Clone, InkBinding)]
+//! #[ink(lookahead_safe, allow_fallback)]
+//! struct PureEvent(i32);
+//! ```
+//!
 //! # Generated Implementation
 //!
 //! The macro generates an implementation that:
@@ -46,6 +64,22 @@
 //! - `InkBindingError::ArgumentsRequired` - No args provided but fields expected
 //! - `InkBindingError::InvalidArguments` - Wrong types provided
 //! - `InkBindingError::TooManyArguments` - More args than fields
+//!
+//! # Ink Globals
+//!
+//! `#[derive(InkGlobals)]` mirrors the global variables of a story into the
+//! named fields of a resource. Each field mirrors the variable with the same
+//! name, unless renamed with `#[ink(name = "...")]`. Fields can be of any type
+//! implementing `FromInkValue`.
+//!
+//! ```ignore
+//! #[derive(Resource, Default, InkGlobals)]
+//! struct Intercept {
+//!     forceful: i32,
+//!     #[ink(name = "teacup")]
+//!     has_teacup: bool,
+//! }
+//! ```
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -64,10 +98,17 @@ struct FieldInfo {
 /// This macro automatically implements the `try_parse_event` method based on
 /// the struct's field types, generating appropriate pattern matching and error
 /// handling for ink function bindings.
-#[proc_macro_derive(InkBinding)]
+#[proc_macro_derive(InkBinding, attributes(ink))]
 pub fn derive_ink_binding(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    let options = match parse_options(&input.attrs) {
+        Ok(options) => options,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let lookahead_safe = options.lookahead_safe;
+    let allow_fallback = options.allow_fallback;
+
     // Extract struct name and generics
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -76,15 +117,14 @@ pub fn derive_ink_binding(input: TokenStream) -> TokenStream {
     let fields = match &input.data {
         Data::Struct(data) => extract_fields(&data.fields),
         _ => {
-            return syn::Error::new_spanned(
-                &input,
-                "InkBinding can only be derived for structs",
-            )
-            .to_compile_error()
-            .into();
+            return syn::Error::new_spanned(&input, "InkBinding can only be derived for structs")
+                .to_compile_error()
+                .into();
         }
     };
 
+    let arity = fields.len();
+
     // Generate match arms based on field count
     let match_arms = if fields.is_empty() {
         // Unit struct: accept empty args only
@@ -101,6 +141,10 @@ pub fn derive_ink_binding(input: TokenStream) -> TokenStream {
         impl #impl_generics InkBindingDefinition for #name #ty_generics #where_clause {
             type Event = Self;
 
+            const LOOKAHEAD_SAFE: bool = #lookahead_safe;
+            const ALLOW_FALLBACK: bool = #allow_fallback;
+            const ARITY: Option<usize> = Some(#arity);
+
             fn try_parse_event(args: &[ValueType]) -> Result<Self::Event, InkBindingError> {
                 match args {
                     #match_arms
@@ -112,6 +156,35 @@ pub fn derive_ink_binding(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Options set through the `#[ink(...)]` attribute
+#[derive(Default)]
+struct BindingOptions {
+    lookahead_safe: bool,
+    allow_fallback: bool,
+}
+
+/// Parses the binding options from the `#[ink(...)]` attributes
+fn parse_options(attrs: &[syn::Attribute]) -> Result<BindingOptions, syn::Error> {
+    let mut options = BindingOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("ink")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("lookahead_safe") {
+                options.lookahead_safe = true;
+                Ok(())
+            } else if meta.path.is_ident("allow_fallback") {
+                options.allow_fallback = true;
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "Unsupported ink binding option. \
+                     Supported options: lookahead_safe, allow_fallback.",
+                ))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
 /// Extracts field information from struct fields
 fn extract_fields(fields: &Fields) -> Vec<FieldInfo> {
     match fields {
@@ -275,3 +348,89 @@ fn generate_constructor(
         }
     }
 }
+
+/// Derives the `InkGlobals` trait for resources mirroring ink variables.
+///
+/// Every named field mirrors the ink variable with the same name, or the one
+/// given with `#[ink(name = "...")]`.
+#[proc_macro_derive(InkGlobals, attributes(ink))]
+pub fn derive_ink_globals(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &input,
+                    "InkGlobals can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(&input, "InkGlobals can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let mut idents = Vec::new();
+    let mut variables = Vec::new();
+    let mut types = Vec::new();
+    for field in fields {
+        let ident = field.ident.clone().unwrap();
+        let variable = match parse_variable_name(&field.attrs) {
+            Ok(variable) => variable.unwrap_or_else(|| ident.to_string()),
+            Err(e) => return e.to_compile_error().into(),
+        };
+        idents.push(ident);
+        variables.push(variable);
+        types.push(field.ty.clone());
+    }
+
+    let expanded = quote! {
+        impl #impl_generics InkGlobals for #name #ty_generics #where_clause {
+            fn variables() -> Vec<(&'static str, InkValueKind)> {
+                vec![#((#variables, <#types as FromInkValue>::KIND)),*]
+            }
+
+            fn set_variable(&mut self, name: &str, value: &InkValue) -> bool {
+                match name {
+                    #(#variables => match <#types as FromInkValue>::from_ink_value(value) {
+                        Some(value) if value != self.#idents => {
+                            self.#idents = value;
+                            true
+                        }
+                        _ => false,
+                    },)*
+                    _ => false,
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Parses the name of the mirrored variable from the `#[ink(name = "...")]`
+/// attribute of a field
+fn parse_variable_name(attrs: &[syn::Attribute]) -> Result<Option<String>, syn::Error> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("ink")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                name = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("Unsupported ink globals option. Supported options: name."))
+            }
+        })?;
+    }
+    Ok(name)
+}